@@ -0,0 +1,48 @@
+//! Compares evaluating a fixed corpus with `eval_all` against calling `evaluate` per expression.
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use pest_calculator::{eval_all, evaluate};
+
+const CORPUS: &[&str] = &[
+    "1 + 2 * 3",
+    "(1 + 2) * 3",
+    "2 ^ 10 - 1",
+    "-7 % 3 + 5!",
+    "max(1, min(2, 3)) * abs(-4)",
+    "sqrt(2) * sqrt(2)",
+    "0xff & 0b1010 | 0o7",
+    "1 < 2 && 3 >= 3 || !true",
+    "1_000_000 / 7",
+    "pi * e",
+];
+
+const ROUNDS: u32 = 2_000;
+
+fn measure(name: &str, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let per_expr = elapsed / (ROUNDS * CORPUS.len() as u32);
+    println!(
+        "{:<12} {:>10.2?} total, {:>8.2?} per expression",
+        name, elapsed, per_expr
+    );
+}
+
+fn main() {
+    measure("eval_all", || {
+        black_box(eval_all(black_box(CORPUS)));
+    });
+    measure("evaluate", || {
+        for input in CORPUS {
+            black_box(evaluate(black_box(input)).ok());
+        }
+    });
+}
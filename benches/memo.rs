@@ -0,0 +1,46 @@
+//! Compares `eval` against `eval_memoized` on trees made of many copies of the same subexpression.
+//!
+//! Run with `cargo bench --bench memo`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use pest::Parser;
+use pest_calculator::{eval, eval_memoized, parse_expr, CalculatorParser, Expr, Rule};
+
+/// Each is added to itself until the tree has 1024 copies of it.
+const OPERANDS: &[&str] = &["1 + 2", "max(sqrt(16), abs(-3)) * pow(2, 10)", "20! / 19!"];
+
+const DEPTH: u32 = 10;
+
+const ROUNDS: u32 = 200;
+
+fn parse(input: &str) -> Expr {
+    let pairs = CalculatorParser::parse(Rule::equation, input).unwrap();
+    parse_expr(pairs).unwrap()
+}
+
+fn measure(name: &str, expr: &Expr, f: fn(&Expr)) {
+    f(expr);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f(black_box(expr));
+    }
+    println!("{:<10} {:>10.2?} per tree", name, start.elapsed() / ROUNDS);
+}
+
+fn main() {
+    for operand in OPERANDS {
+        let input = (0..DEPTH).fold(operand.to_string(), |expr, _| {
+            format!("({0}) + ({0})", expr)
+        });
+        let expr = parse(&input);
+        println!("{}:", operand);
+        measure("eval", &expr, |expr| {
+            black_box(eval(expr).unwrap());
+        });
+        measure("memoized", &expr, |expr| {
+            black_box(eval_memoized(expr).unwrap());
+        });
+    }
+}
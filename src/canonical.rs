@@ -0,0 +1,108 @@
+use crate::{Expr, ExprKind, Op};
+
+/// Rewrites `expr` so that expressions that only differ in the order of the operands of `+` and `*`
+/// become structurally equal, e.g. `3 + 1 + 2` and `(2 + 1) + 3` both become `1 + 2 + 3`.
+///
+/// Chains of the same commutative operator are flattened, including parenthesized ones, and their operands
+/// are sorted by how they are written, after canonicalizing them in turn. The sorted chain is rebuilt to the left.
+/// Every other operator, including `-`, `/` and `%`, keeps its operands in place.
+/// Compare the results with [`Expr::structurally_eq`], since spans are kept but no longer in order.
+///
+/// This is meant for comparing expressions rather than evaluating them: reordering can change which
+/// overflow or rounding occurs, and in what order assignments within the operands happen.
+pub fn canonicalize(expr: Expr) -> Expr {
+    let span = expr.span;
+    let kind = match expr.kind {
+        ExprKind::BinOp {
+            lhs,
+            op: op @ (Op::Add | Op::Multiply),
+            rhs,
+        } => {
+            let mut operands = Vec::new();
+            flatten(*lhs, &op, &mut operands);
+            flatten(*rhs, &op, &mut operands);
+            let mut operands: Vec<(String, Expr)> = operands
+                .into_iter()
+                .map(|operand| {
+                    let operand = canonicalize(operand);
+                    (operand.to_string(), operand)
+                })
+                .collect();
+            operands.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let mut operands = operands.into_iter().map(|(_, operand)| operand);
+            let first = operands.next().unwrap();
+            let chain = operands.fold(first, |lhs, rhs| {
+                let span = lhs.span.to(rhs.span);
+                let kind = ExprKind::BinOp {
+                    lhs: Box::new(lhs),
+                    op: op.clone(),
+                    rhs: Box::new(rhs),
+                };
+                Expr::new(kind, span)
+            });
+            chain.kind
+        }
+        ExprKind::BinOp { lhs, op, rhs } => ExprKind::BinOp {
+            lhs: canonicalize_boxed(lhs),
+            op,
+            rhs: canonicalize_boxed(rhs),
+        },
+        ExprKind::UnaryMinus(operand) => ExprKind::UnaryMinus(canonicalize_boxed(operand)),
+        ExprKind::Not(operand) => ExprKind::Not(canonicalize_boxed(operand)),
+        ExprKind::Factorial(operand) => ExprKind::Factorial(canonicalize_boxed(operand)),
+        ExprKind::Percent(operand) => ExprKind::Percent(canonicalize_boxed(operand)),
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(canonicalize).collect(),
+        },
+        ExprKind::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => ExprKind::Conditional {
+            cond: canonicalize_boxed(cond),
+            then: canonicalize_boxed(then),
+            otherwise: canonicalize_boxed(otherwise),
+        },
+        ExprKind::Assign { name, value } => ExprKind::Assign {
+            name,
+            value: canonicalize_boxed(value),
+        },
+        ExprKind::Let { name, value, body } => ExprKind::Let {
+            name,
+            value: canonicalize_boxed(value),
+            body: canonicalize_boxed(body),
+        },
+        ExprKind::Range { start, end } => ExprKind::Range {
+            start: canonicalize_boxed(start),
+            end: canonicalize_boxed(end),
+        },
+        kind @ (ExprKind::Integer(..)
+        | ExprKind::Float(_)
+        | ExprKind::Bool(_)
+        | ExprKind::Constant(_)
+        | ExprKind::Variable(_)) => kind,
+    };
+    Expr::new(kind, span)
+}
+
+/// Canonicalizes a boxed operand, reusing its allocation.
+fn canonicalize_boxed(mut expr: Box<Expr>) -> Box<Expr> {
+    *expr = canonicalize(*expr);
+    expr
+}
+
+/// Collects the operands of the chain of `op` that `expr` is part of, in source order.
+fn flatten(expr: Expr, op: &Op, operands: &mut Vec<Expr>) {
+    match expr.kind {
+        ExprKind::BinOp {
+            lhs,
+            op: ref other,
+            rhs,
+        } if other == op => {
+            flatten(*lhs, op, operands);
+            flatten(*rhs, op, operands);
+        }
+        kind => operands.push(Expr::new(kind, expr.span)),
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::format::format_int;
+use crate::int;
+use crate::{Associativity, Expr, ExprKind, Op, UnaryOp};
+
+// Binding strength of each kind of node, higher binds tighter.
+// Assignment and conditionals bind looser than every operator, and prefix operators are part of an atom
+// in the grammar, so they bind tighter than every infix operator. The exception is unary minus, whose operand
+// extends over `^`: it binds like `^`, so it needs parentheses on the left of `^` but not on its right.
+const ASSIGN: u8 = 0;
+const CONDITIONAL: u8 = 1;
+const NEGATION: u8 = op_precedence(&Op::Power);
+const PREFIX: u8 = NEGATION + 1;
+const POSTFIX: u8 = PREFIX + 1;
+const ATOM: u8 = POSTFIX + 1;
+
+/// The precedence of an infix operator among the other nodes, which is above that of conditionals.
+const fn op_precedence(op: &Op) -> u8 {
+    CONDITIONAL + op.precedence()
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match &expr.kind {
+        // Values that no literal can write are written as subtractions, see `fmt` below.
+        ExprKind::Integer(i, _) if int::is_min(i) => op_precedence(&Op::Subtract),
+        ExprKind::Float(x) if x.is_nan() => op_precedence(&Op::Subtract),
+        // A negative literal is written with a minus, which reads as a negation, e.g. `(-2) ^ 2`.
+        ExprKind::Integer(i, _) if int::is_negative(i) => NEGATION,
+        ExprKind::Float(x) if x.is_sign_negative() => NEGATION,
+        ExprKind::Integer(..)
+        | ExprKind::Float(_)
+        | ExprKind::Bool(_)
+        | ExprKind::Constant(_)
+        | ExprKind::Variable(_)
+        | ExprKind::Call { .. } => ATOM,
+        ExprKind::UnaryMinus(_) => NEGATION,
+        ExprKind::Not(_) => PREFIX,
+        ExprKind::Factorial(_) | ExprKind::Percent(_) => POSTFIX,
+        ExprKind::BinOp { op, .. } => op_precedence(op),
+        ExprKind::Conditional { .. } => CONDITIONAL,
+        ExprKind::Assign { .. } | ExprKind::Let { .. } | ExprKind::Range { .. } => ASSIGN,
+    }
+}
+
+/// Writes `expr`, wrapped in parentheses if it binds less tightly than `min_precedence`.
+fn write_operand(f: &mut fmt::Formatter, expr: &Expr, min_precedence: u8) -> fmt::Result {
+    if precedence(expr) < min_precedence {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Op::Add => "+",
+            Op::Subtract => "-",
+            Op::Multiply => "*",
+            Op::Divide => "/",
+            Op::FloorDivide => "//",
+            Op::Modulo => "%",
+            Op::Power => "^",
+            Op::Less => "<",
+            Op::LessEqual => "<=",
+            Op::Greater => ">",
+            Op::GreaterEqual => ">=",
+            Op::Equal => "==",
+            Op::NotEqual => "!=",
+            Op::And => "&&",
+            Op::Or => "||",
+            Op::BitAnd => "&",
+            Op::BitOr => "|",
+            Op::BitXor => "~",
+            Op::ShiftLeft => "<<",
+            Op::ShiftRight => ">>",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// Names the operator like error messages do, since prefix `!` and postfix `!` share a symbol.
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            UnaryOp::Minus => "unary -",
+            UnaryOp::Not => "!",
+            UnaryOp::Factorial => "postfix !",
+            UnaryOp::Percent => "postfix %",
+        })
+    }
+}
+
+/// Renders the expression in infix notation, with only the parentheses needed to preserve
+/// precedence and associativity.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            // The literal after the minus would not fit, so the smallest integer is written as the one above it minus 1.
+            ExprKind::Integer(i, radix) if int::is_min(i) => {
+                write!(f, "{} - 1", format_int(&(i.clone() + 1), *radix))
+            }
+            // Written in the radix of the literal, so `0xff` stays `0xff`.
+            ExprKind::Integer(i, radix) => f.write_str(&format_int(i, *radix)),
+            // Literals too large for a float parse as infinity, and subtracting infinities gives NaN.
+            ExprKind::Float(x) if x.is_nan() => f.write_str("1e999 - 1e999"),
+            ExprKind::Float(x) if x.is_infinite() => {
+                f.write_str(if *x > 0.0 { "1e999" } else { "-1e999" })
+            }
+            // Debug formatting keeps a fractional part or exponent, so the output parses as a float again.
+            ExprKind::Float(x) => write!(f, "{:?}", x),
+            ExprKind::Bool(b) => write!(f, "{}", b),
+            ExprKind::Constant(constant) => f.write_str(constant.name()),
+            ExprKind::Variable(name) => f.write_str(name),
+            ExprKind::Call { name, args } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                f.write_str(")")
+            }
+            ExprKind::UnaryMinus(operand) => {
+                f.write_str("-")?;
+                write_operand(f, operand, NEGATION)
+            }
+            ExprKind::Not(operand) => {
+                f.write_str("!")?;
+                write_operand(f, operand, PREFIX)
+            }
+            ExprKind::Factorial(operand) => {
+                write_operand(f, operand, POSTFIX)?;
+                f.write_str("!")
+            }
+            ExprKind::Percent(operand) => {
+                write_operand(f, operand, POSTFIX)?;
+                f.write_str("%")
+            }
+            ExprKind::BinOp { lhs, op, rhs } => {
+                let precedence = op_precedence(op);
+                let (lhs_min, rhs_min) = match op.associativity() {
+                    Associativity::Left => (precedence, precedence + 1),
+                    Associativity::Right => (precedence + 1, precedence),
+                };
+                write_operand(f, lhs, lhs_min)?;
+                write!(f, " {} ", op)?;
+                write_operand(f, rhs, rhs_min)
+            }
+            // Nested conditionals are only left unparenthesized in the otherwise branch, where they associate.
+            ExprKind::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                write_operand(f, cond, CONDITIONAL + 1)?;
+                f.write_str(" ? ")?;
+                write_operand(f, then, CONDITIONAL + 1)?;
+                f.write_str(" : ")?;
+                write_operand(f, otherwise, CONDITIONAL)
+            }
+            ExprKind::Assign { name, value } => write!(f, "{} = {}", name, value),
+            ExprKind::Let { name, value, body } => {
+                write!(f, "let {} = {} in {}", name, value, body)
+            }
+            // Both ends are whole expressions in the grammar, which stop before the `..`.
+            ExprKind::Range { start, end } => write!(f, "{}..{}", start, end),
+        }
+    }
+}
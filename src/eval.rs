@@ -0,0 +1,654 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::functions;
+use crate::int;
+use crate::number::{arithmetic, Number};
+use crate::{CalcError, Expr, ExprKind, Int, Op, ParseConfig, Rule, Span, UnaryOp};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum EvalErrorKind {
+    DivisionByZero,
+    /// An integer division left a remainder while [`EvalConfig::strict_integer_division`] is on.
+    InexactDivision,
+    /// The named operation produced a value that does not fit in an [`Int`].
+    Overflow(&'static str),
+    NegativeFactorial,
+    NonIntegerFactorial,
+    UndefinedVariable(String),
+    /// `ans` was used before any statement produced a result.
+    NoPreviousResult,
+    UnknownFunction(String),
+    ArityMismatch {
+        function: String,
+        expected: usize,
+        /// Whether the function takes `expected` or more arguments, like `max`, rather than exactly that many.
+        variadic: bool,
+        found: usize,
+    },
+    /// The arguments of the named function are of the right types but cannot be used together,
+    /// such as bounds in the wrong order.
+    InvalidArguments {
+        function: String,
+        reason: &'static str,
+    },
+    /// A shift by a negative amount or by at least the width of an integer.
+    InvalidShift(Int),
+    /// The binary operator is turned off in [`EnabledOperators`].
+    OperatorDisabled(Op),
+    /// The prefix or postfix operator is turned off in [`EnabledOperators`].
+    UnaryOperatorDisabled(UnaryOp),
+    /// The named function only takes a range, e.g. `sum(1, 2)` instead of `sum(1..2)`.
+    ExpectedRange(String),
+    /// A range was passed to a function other than `sum` and `product`.
+    MisplacedRange,
+    /// Tokens passed to [`Environment::eval_rpn`] left no value or several values on the stack.
+    ///
+    /// [`Environment::eval_rpn`]: crate::Environment::eval_rpn
+    MalformedRpn,
+    /// A range has more elements than [`EvalConfig::max_range`].
+    RangeTooLarge {
+        max: u64,
+    },
+    /// Evaluation took more than [`EvalConfig::max_operations`] operations.
+    OperationLimitExceeded {
+        max: u64,
+    },
+    /// An operand had the wrong type, e.g. a bool where a number was expected.
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A part of an expression that [`Environment::eval_as`] cannot evaluate in the named [`Number`] type.
+    ///
+    /// [`Number`]: crate::Number
+    Unsupported {
+        construct: &'static str,
+        number: &'static str,
+    },
+}
+
+impl fmt::Display for EvalErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalErrorKind::DivisionByZero => write!(f, "division by zero"),
+            EvalErrorKind::InexactDivision => write!(f, "integer division has a remainder"),
+            EvalErrorKind::Overflow(operation) => write!(f, "integer overflow in {}", operation),
+            EvalErrorKind::NegativeFactorial => write!(f, "factorial of a negative number"),
+            EvalErrorKind::NonIntegerFactorial => write!(f, "factorial of a non-integer"),
+            EvalErrorKind::UndefinedVariable(name) => write!(f, "undefined variable {}", name),
+            EvalErrorKind::NoPreviousResult => write!(f, "ans used before any result"),
+            EvalErrorKind::UnknownFunction(name) => write!(f, "unknown function {}", name),
+            EvalErrorKind::ArityMismatch {
+                function,
+                expected,
+                variadic,
+                found,
+            } => write!(
+                f,
+                "{} expects {}{} argument(s) but got {}",
+                function,
+                if *variadic { "at least " } else { "" },
+                expected,
+                found
+            ),
+            EvalErrorKind::InvalidArguments { function, reason } => {
+                write!(f, "invalid arguments to {}: {}", function, reason)
+            }
+            EvalErrorKind::InvalidShift(amount) => write!(f, "invalid shift amount {}", amount),
+            EvalErrorKind::OperatorDisabled(operator) => {
+                write!(f, "operator {} is disabled", operator)
+            }
+            EvalErrorKind::UnaryOperatorDisabled(operator) => {
+                write!(f, "operator {} is disabled", operator)
+            }
+            EvalErrorKind::ExpectedRange(function) => {
+                write!(f, "{} expects a range like 1..10", function)
+            }
+            EvalErrorKind::MisplacedRange => {
+                write!(f, "a range can only be the argument of sum or product")
+            }
+            EvalErrorKind::MalformedRpn => write!(f, "tokens do not form a single expression"),
+            EvalErrorKind::RangeTooLarge { max } => {
+                write!(f, "range has more than {} elements", max)
+            }
+            EvalErrorKind::OperationLimitExceeded { max } => {
+                write!(f, "evaluation took more than {} operations", max)
+            }
+            EvalErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "type mismatch: expected {}, found {}", expected, found)
+            }
+            EvalErrorKind::Unsupported { construct, number } => {
+                write!(f, "{} cannot be evaluated as {}", construct, number)
+            }
+        }
+    }
+}
+
+/// An evaluation error, together with the span of the subexpression that caused it.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    pub span: Span,
+}
+
+impl EvalError {
+    pub fn new(kind: EvalErrorKind, span: Span) -> Self {
+        EvalError { kind, span }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// The result of evaluating an expression.
+///
+/// Values are `Copy` unless the `bignum` feature makes [`Int`] an arbitrary-precision integer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "bignum"), derive(Copy))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    Int(Int),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+        }
+    }
+
+    /// Converts a number to `f64`, failing for non-numeric values.
+    pub(crate) fn as_f64(&self) -> Result<f64, EvalErrorKind> {
+        match self {
+            Value::Int(i) => Ok(int::to_f64(i)),
+            Value::Float(f) => Ok(*f),
+            Value::Bool(_) => Err(self.mismatch("a number")),
+        }
+    }
+
+    pub(crate) fn mismatch(&self, expected: &'static str) -> EvalErrorKind {
+        EvalErrorKind::TypeMismatch {
+            expected,
+            found: self.type_name(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{}", i),
+            // Debug formatting always keeps a fractional part or exponent, so `1000.0` does not look like an integer.
+            Value::Float(x) => write!(f, "{:?}", x),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Evaluates an expression that does not refer to any variables.
+///
+/// See [`Environment::eval`] for the evaluation rules.
+pub fn eval(expr: &Expr) -> Result<Value, EvalError> {
+    Environment::new().eval(expr)
+}
+
+/// How `%` rounds when its operands have different signs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModuloMode {
+    /// The remainder of division rounded towards zero, which has the sign of the left operand,
+    /// so `-7 % 3` is `-1` and `7 % -3` is `1`. This is how `%` works in Rust and C.
+    #[default]
+    Truncated,
+    /// The remainder is never negative, so `-7 % 3` is `2` and `7 % -3` is `1`.
+    Euclidean,
+}
+
+/// Which kinds of operators may be evaluated, e.g. to offer a restricted calculator.
+///
+/// Evaluating a disabled operator fails with [`EvalErrorKind::OperatorDisabled`] before its operands
+/// are evaluated. Built-in functions such as `pow` are not affected. Everything is enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnabledOperators {
+    /// `+`, `-`, `*`, `/` and `//`.
+    pub arithmetic: bool,
+    /// Infix `%`.
+    pub modulo: bool,
+    /// `^`.
+    pub power: bool,
+    /// Prefix `-`.
+    pub unary_minus: bool,
+    /// Postfix `!`.
+    pub factorial: bool,
+    /// Postfix `%`.
+    pub percent: bool,
+    /// `<`, `<=`, `>`, `>=`, `==` and `!=`.
+    pub comparison: bool,
+    /// `&&`, `||` and prefix `!`.
+    pub logical: bool,
+    /// `&`, `|`, `~`, `<<` and `>>`.
+    pub bitwise: bool,
+}
+
+impl Default for EnabledOperators {
+    fn default() -> Self {
+        EnabledOperators {
+            arithmetic: true,
+            modulo: true,
+            power: true,
+            unary_minus: true,
+            factorial: true,
+            percent: true,
+            comparison: true,
+            logical: true,
+            bitwise: true,
+        }
+    }
+}
+
+impl EnabledOperators {
+    /// Fails if the operator at the root of `kind` is disabled.
+    pub(crate) fn check(&self, kind: &ExprKind) -> Result<(), EvalErrorKind> {
+        use EvalErrorKind::UnaryOperatorDisabled;
+        let (enabled, error) = match kind {
+            ExprKind::UnaryMinus(_) => (self.unary_minus, UnaryOperatorDisabled(UnaryOp::Minus)),
+            ExprKind::Not(_) => (self.logical, UnaryOperatorDisabled(UnaryOp::Not)),
+            ExprKind::Factorial(_) => (self.factorial, UnaryOperatorDisabled(UnaryOp::Factorial)),
+            ExprKind::Percent(_) => (self.percent, UnaryOperatorDisabled(UnaryOp::Percent)),
+            ExprKind::BinOp { op, .. } => {
+                let enabled = match op {
+                    Op::Add | Op::Subtract | Op::Multiply | Op::Divide | Op::FloorDivide => {
+                        self.arithmetic
+                    }
+                    Op::Modulo => self.modulo,
+                    Op::Power => self.power,
+                    Op::Less
+                    | Op::LessEqual
+                    | Op::Greater
+                    | Op::GreaterEqual
+                    | Op::Equal
+                    | Op::NotEqual => self.comparison,
+                    Op::And | Op::Or => self.logical,
+                    Op::BitAnd | Op::BitOr | Op::BitXor | Op::ShiftLeft | Op::ShiftRight => {
+                        self.bitwise
+                    }
+                };
+                (enabled, EvalErrorKind::OperatorDisabled(op.clone()))
+            }
+            _ => return Ok(()),
+        };
+        if enabled {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+}
+
+/// Settings that change how expressions are evaluated.
+#[derive(Debug, Clone, Copy)]
+pub struct EvalConfig {
+    pub modulo: ModuloMode,
+    pub operators: EnabledOperators,
+    /// The most elements a range passed to `sum` or `product` may have, one million by default.
+    pub max_range: u64,
+    /// Whether dividing integers that do not divide evenly is an error rather than truncating, off by default.
+    pub strict_integer_division: bool,
+    /// Whether `/` always divides in floating point like in Python, so `7 / 2` is `3.5` and `6 / 3` is `2.0`,
+    /// rather than dividing integers to an integer. Off by default. `//` still divides integers to an integer.
+    pub float_division: bool,
+    /// The most operations a single call to [`Environment::eval`] may perform, effectively unlimited by default.
+    ///
+    /// Evaluating any node counts as one operation, and `sum` and `product` count one per element of their range.
+    /// The other evaluators, like [`Environment::eval_steps`] and [`Environment::eval_rpn`], count every node
+    /// they evaluate the same way over the whole call, including the values they substitute for operands.
+    pub max_operations: u64,
+}
+
+impl Default for EvalConfig {
+    fn default() -> Self {
+        EvalConfig {
+            modulo: ModuloMode::default(),
+            operators: EnabledOperators::default(),
+            max_range: 1_000_000,
+            strict_integer_division: false,
+            float_division: false,
+            max_operations: u64::MAX,
+        }
+    }
+}
+
+/// The variables defined so far, which persist across statements.
+#[derive(Debug, Default)]
+pub struct Environment {
+    variables: HashMap<String, Value>,
+    /// The bindings of the `let` expressions being evaluated, innermost last.
+    scopes: Vec<HashMap<String, Value>>,
+    config: EvalConfig,
+    /// Whether `ans` refers to `previous`, see [`Environment::track_ans`].
+    track_ans: bool,
+    previous: Option<Value>,
+    /// The operations performed by the current call to [`Environment::eval`] or another evaluator.
+    operations: u64,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty environment that evaluates according to `config`.
+    pub fn with_config(config: EvalConfig) -> Self {
+        Environment {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn config(&self) -> &EvalConfig {
+        &self.config
+    }
+
+    /// Looks up a variable, preferring the innermost `let` binding of that name.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .chain([&self.variables])
+            .find_map(|scope| scope.get(name))
+            .cloned()
+    }
+
+    /// Assigns to the innermost `let` binding of `name`, or defines a global variable if there is none.
+    pub fn set(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        let scope = match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.contains_key(&name))
+        {
+            Some(scope) => scope,
+            None => &mut self.variables,
+        };
+        scope.insert(name, value);
+    }
+
+    /// Evaluates `body` with `name` bound to `value`, hiding any variable of the same name meanwhile.
+    fn eval_scoped(&mut self, name: &str, value: Value, body: &Expr) -> Result<Value, EvalError> {
+        self.scopes.push(HashMap::from([(name.to_owned(), value)]));
+        let result = self.eval_node(body);
+        self.scopes.pop();
+        result
+    }
+
+    /// Makes `ans` evaluate to the last value passed to [`Environment::set_ans`], like in the REPL.
+    ///
+    /// Until a value has been set, using `ans` fails with [`EvalErrorKind::NoPreviousResult`].
+    /// A variable assigned to with the name `ans` hides the previous result.
+    pub fn track_ans(&mut self) {
+        self.track_ans = true;
+    }
+
+    /// Records `value` as the previous result, which `ans` refers to if [`Environment::track_ans`] was called.
+    pub fn set_ans(&mut self, value: Value) {
+        self.previous = Some(value);
+    }
+
+    /// Parses and evaluates a line of semicolon separated statements, returning the value of the last one.
+    ///
+    /// Evaluation stops at the first statement that fails, skipping the statements after it.
+    pub fn eval_line(&mut self, line: &str) -> Result<Value, CalcError> {
+        let config = ParseConfig::default();
+        let statements = config.parse_program(config.parse(Rule::program, line)?)?;
+        let mut value = None;
+        for statement in &statements {
+            value = Some(self.eval(statement)?);
+        }
+        // `program` requires at least one statement.
+        Ok(value.unwrap())
+    }
+
+    /// Recursively evaluates an expression tree, looking up and assigning variables in this environment.
+    ///
+    /// Integer operands use checked [`Int`] arithmetic, so `7 / 2` is `3`, `-7 % 3` is `-1` (see [`ModuloMode`]),
+    /// and overflow is reported the same way in debug and release builds.
+    /// As soon as either operand of a binary operator is a float, the other one is promoted to `f64`
+    /// and the operation is performed in floating point, e.g. `2 + 3.5` is `5.5`.
+    /// Dividing by zero is an error for both integers and floats.
+    /// An integer raised to a negative integer power has no integer result, so `2 ^ -1` is computed as a float.
+    ///
+    /// Errors carry the span of the innermost subexpression that failed, e.g. the whole `6 / 0` for a division by zero.
+    /// An assignment evaluates to the assigned value, so `y = (x = 3) + 1` sets both `x` and `y`.
+    /// `let x = 2 in x * x` binds `x` only while evaluating `x * x`, so no variable `x` is defined afterwards.
+    ///
+    /// Every node that is evaluated, and every element of a range, counts towards [`EvalConfig::max_operations`].
+    pub fn eval(&mut self, expr: &Expr) -> Result<Value, EvalError> {
+        self.reset_operations();
+        self.eval_node(expr)
+    }
+
+    /// Starts counting towards [`EvalConfig::max_operations`] from zero, at the start of an evaluation.
+    pub(crate) fn reset_operations(&mut self) {
+        self.operations = 0;
+    }
+
+    /// Evaluates `expr` as part of the evaluation in progress, adding to the operations counted so far.
+    pub(crate) fn eval_node(&mut self, expr: &Expr) -> Result<Value, EvalError> {
+        let at = |kind| EvalError::new(kind, expr.span);
+        self.count(1).map_err(at)?;
+        self.config.operators.check(&expr.kind).map_err(at)?;
+        match &expr.kind {
+            ExprKind::Integer(i, _) => Ok(Value::Int(i.clone())),
+            ExprKind::Float(f) => Ok(Value::Float(*f)),
+            ExprKind::Bool(b) => Ok(Value::Bool(*b)),
+            ExprKind::Constant(constant) => Ok(Value::Float(constant.value())),
+            ExprKind::Variable(name) => match self.get(name) {
+                Some(value) => Ok(value),
+                None if self.track_ans && name == "ans" => self
+                    .previous
+                    .clone()
+                    .ok_or(at(EvalErrorKind::NoPreviousResult)),
+                None => Err(at(EvalErrorKind::UndefinedVariable(name.clone()))),
+            },
+            ExprKind::UnaryMinus(operand) => match self.eval_node(operand)? {
+                Value::Int(i) => Number::checked_neg(i).map(Value::Int).map_err(at),
+                Value::Float(f) => Ok(Value::Float(-f)),
+                value => Err(at(value.mismatch("a number"))),
+            },
+            ExprKind::Not(operand) => match self.eval_node(operand)? {
+                Value::Bool(b) => Ok(Value::Bool(!b)),
+                value => Err(at(value.mismatch("bool"))),
+            },
+            ExprKind::Factorial(operand) => match self.eval_node(operand)? {
+                Value::Int(n) if int::is_negative(&n) => Err(at(EvalErrorKind::NegativeFactorial)),
+                Value::Int(n) => int::factorial(&n)
+                    .map(Value::Int)
+                    .ok_or(at(EvalErrorKind::Overflow("factorial"))),
+                Value::Float(_) => Err(at(EvalErrorKind::NonIntegerFactorial)),
+                value => Err(at(value.mismatch("a number"))),
+            },
+            ExprKind::Percent(operand) => {
+                let x = self.eval_node(operand)?.as_f64().map_err(at)?;
+                Ok(Value::Float(x / 100.0))
+            }
+            ExprKind::BinOp {
+                lhs,
+                op: op @ (Op::And | Op::Or),
+                rhs,
+            } => {
+                // Short-circuit, so the right hand side is only evaluated if it decides the result.
+                match (op, self.eval_node(lhs)?) {
+                    (Op::And, Value::Bool(false)) => Ok(Value::Bool(false)),
+                    (Op::Or, Value::Bool(true)) => Ok(Value::Bool(true)),
+                    (_, lhs) => apply(lhs, op, self.eval_node(rhs)?, &self.config).map_err(at),
+                }
+            }
+            ExprKind::BinOp { lhs, op, rhs } => {
+                apply(self.eval_node(lhs)?, op, self.eval_node(rhs)?, &self.config).map_err(at)
+            }
+            ExprKind::Call { name, args } => match args.as_slice() {
+                // A range is folded over without evaluating it to a value.
+                [Expr {
+                    kind: ExprKind::Range { start, end },
+                    ..
+                }] => {
+                    let start = self.eval_bound(start)?;
+                    let end = self.eval_bound(end)?;
+                    let config = self.config;
+                    functions::aggregate(name, start, end, &config, |len| self.count(len))
+                        .map_err(at)
+                }
+                _ => {
+                    let args = args
+                        .iter()
+                        .map(|arg| self.eval_node(arg))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    functions::call(name, &args, &self.config).map_err(at)
+                }
+            },
+            ExprKind::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => match self.eval_node(cond)? {
+                Value::Bool(true) => self.eval_node(then),
+                Value::Bool(false) => self.eval_node(otherwise),
+                value => Err(EvalError::new(value.mismatch("bool"), cond.span)),
+            },
+            ExprKind::Assign { name, value } => {
+                let value = self.eval_node(value)?;
+                self.set(name.as_str(), value.clone());
+                Ok(value)
+            }
+            ExprKind::Let { name, value, body } => {
+                let value = self.eval_node(value)?;
+                self.eval_scoped(name, value, body)
+            }
+            ExprKind::Range { .. } => Err(at(EvalErrorKind::MisplacedRange)),
+        }
+    }
+
+    /// Counts `operations` more operations of the current evaluation, failing once there are too many.
+    fn count(&mut self, operations: u64) -> Result<(), EvalErrorKind> {
+        self.operations = self.operations.saturating_add(operations);
+        if self.operations > self.config.max_operations {
+            return Err(EvalErrorKind::OperationLimitExceeded {
+                max: self.config.max_operations,
+            });
+        }
+        Ok(())
+    }
+
+    /// Evaluates the start or end of a range, which has to be an integer.
+    fn eval_bound(&mut self, bound: &Expr) -> Result<Int, EvalError> {
+        match self.eval_node(bound)? {
+            Value::Int(i) => Ok(i),
+            value => Err(EvalError::new(value.mismatch("int"), bound.span)),
+        }
+    }
+}
+
+/// Applies a binary operator to two evaluated operands.
+///
+/// Comparisons are defined between any two numbers, and `==` and `!=` also between two bools.
+/// Comparing a bool with a number is a type mismatch rather than simply unequal.
+/// The logical operators require two bools, and the bitwise operators two integers.
+pub(crate) fn apply(
+    lhs: Value,
+    op: &Op,
+    rhs: Value,
+    config: &EvalConfig,
+) -> Result<Value, EvalErrorKind> {
+    if is_comparison(op) {
+        return compare(lhs, op, rhs).map(Value::Bool);
+    }
+    if let Op::And | Op::Or = op {
+        return match (lhs, rhs) {
+            (Value::Bool(lhs), Value::Bool(rhs)) if matches!(op, Op::And) => {
+                Ok(Value::Bool(lhs && rhs))
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => Ok(Value::Bool(lhs || rhs)),
+            (Value::Bool(_), other) | (other, _) => Err(other.mismatch("bool")),
+        };
+    }
+    if is_bitwise(op) {
+        return match (lhs, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => eval_bitwise(lhs, op, rhs).map(Value::Int),
+            (Value::Int(_), other) | (other, _) => Err(other.mismatch("int")),
+        };
+    }
+    // An integer raised to a negative power has no integer result, so it is computed as a float.
+    // With `float_division`, `/` is computed as a float too.
+    let in_floats = |rhs: &Int| match op {
+        Op::Power => int::is_negative(rhs),
+        Op::Divide => config.float_division,
+        _ => false,
+    };
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) if !in_floats(&rhs) => {
+            arithmetic(lhs, op, rhs, config).map(Value::Int)
+        }
+        (lhs, rhs) => arithmetic(lhs.as_f64()?, op, rhs.as_f64()?, config).map(Value::Float),
+    }
+}
+
+fn is_comparison(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::Less | Op::LessEqual | Op::Greater | Op::GreaterEqual | Op::Equal | Op::NotEqual
+    )
+}
+
+fn is_bitwise(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::BitAnd | Op::BitOr | Op::BitXor | Op::ShiftLeft | Op::ShiftRight
+    )
+}
+
+fn eval_bitwise(lhs: Int, op: &Op, rhs: Int) -> Result<Int, EvalErrorKind> {
+    match op {
+        Op::BitAnd => Ok(lhs & rhs),
+        Op::BitOr => Ok(lhs | rhs),
+        Op::BitXor => Ok(lhs ^ rhs),
+        Op::ShiftLeft => int::shift_left(&lhs, &rhs).ok_or(EvalErrorKind::InvalidShift(rhs)),
+        Op::ShiftRight => int::shift_right(&lhs, &rhs).ok_or(EvalErrorKind::InvalidShift(rhs)),
+        _ => unreachable!("{:?} is not a bitwise operator", op),
+    }
+}
+
+fn compare(lhs: Value, op: &Op, rhs: Value) -> Result<bool, EvalErrorKind> {
+    let ordering = match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => lhs.partial_cmp(&rhs),
+        (Value::Bool(lhs), Value::Bool(rhs)) => match op {
+            Op::Equal => return Ok(lhs == rhs),
+            Op::NotEqual => return Ok(lhs != rhs),
+            _ => return Err(Value::Bool(lhs).mismatch("a number")),
+        },
+        (Value::Bool(_), other) | (other, Value::Bool(_)) => return Err(other.mismatch("bool")),
+        (lhs, rhs) => lhs.as_f64()?.partial_cmp(&rhs.as_f64()?),
+    };
+    // `ordering` is only `None` when comparing against NaN, which is unequal to everything.
+    Ok(match op {
+        Op::Less => ordering == Some(Ordering::Less),
+        Op::LessEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        Op::Greater => ordering == Some(Ordering::Greater),
+        Op::GreaterEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        Op::Equal => ordering == Some(Ordering::Equal),
+        Op::NotEqual => ordering != Some(Ordering::Equal),
+        _ => unreachable!("{:?} is not a comparison", op),
+    })
+}
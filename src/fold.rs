@@ -0,0 +1,128 @@
+use crate::{Environment, EvalConfig, Expr, ExprKind, Value};
+
+/// Replaces every subtree that does not depend on a variable by its value.
+///
+/// For example `2 * 3 + x` becomes `6 + x`. Subtrees whose evaluation fails, such as `1 / 0`,
+/// are kept as they are, so the error is still reported when the whole expression is evaluated.
+/// Operands are not reordered, so `x + 2 + 3` is left unchanged.
+/// A folded node keeps the span of the subtree it replaces.
+///
+/// Subtrees are evaluated with the default [`EvalConfig`]; see [`Environment::fold_constants`] to fold with another one.
+pub fn fold_constants(expr: Expr) -> Expr {
+    Environment::new().fold_constants(expr)
+}
+
+impl Environment {
+    /// Folds constants like [`fold_constants`], evaluating subtrees according to the [`EvalConfig`] of this environment.
+    ///
+    /// The folded tree evaluates the same as `expr` in this environment, so for example `-7 % 3` folds to `2`
+    /// with [`ModuloMode::Euclidean`](crate::ModuloMode::Euclidean), and operators that are disabled in
+    /// [`EvalConfig::operators`] stay unfolded. Variables of the environment are not substituted.
+    pub fn fold_constants(&self, expr: Expr) -> Expr {
+        fold(expr, self.config())
+    }
+}
+
+fn fold(expr: Expr, config: &EvalConfig) -> Expr {
+    let span = expr.span;
+    let kind = match expr.kind {
+        ExprKind::UnaryMinus(operand) => ExprKind::UnaryMinus(fold_boxed(operand, config)),
+        ExprKind::Not(operand) => ExprKind::Not(fold_boxed(operand, config)),
+        ExprKind::Factorial(operand) => ExprKind::Factorial(fold_boxed(operand, config)),
+        ExprKind::Percent(operand) => ExprKind::Percent(fold_boxed(operand, config)),
+        ExprKind::BinOp { lhs, op, rhs } => ExprKind::BinOp {
+            lhs: fold_boxed(lhs, config),
+            op,
+            rhs: fold_boxed(rhs, config),
+        },
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(|arg| fold(arg, config)).collect(),
+        },
+        // With a constant condition only the branch that is taken remains.
+        ExprKind::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => {
+            let cond = fold_boxed(cond, config);
+            match cond.kind {
+                ExprKind::Bool(true) => return fold(*then, config),
+                ExprKind::Bool(false) => return fold(*otherwise, config),
+                _ => ExprKind::Conditional {
+                    cond,
+                    then: fold_boxed(then, config),
+                    otherwise: fold_boxed(otherwise, config),
+                },
+            }
+        }
+        // The assignment itself is a side effect, so only its value is folded.
+        ExprKind::Assign { name, value } => {
+            let value = fold_boxed(value, config);
+            return Expr::new(ExprKind::Assign { name, value }, span);
+        }
+        // The body may refer to the binding, so the `let` itself remains.
+        ExprKind::Let { name, value, body } => {
+            let value = fold_boxed(value, config);
+            let body = fold_boxed(body, config);
+            return Expr::new(ExprKind::Let { name, value, body }, span);
+        }
+        ExprKind::Range { start, end } => ExprKind::Range {
+            start: fold_boxed(start, config),
+            end: fold_boxed(end, config),
+        },
+        kind @ (ExprKind::Integer(..)
+        | ExprKind::Float(_)
+        | ExprKind::Bool(_)
+        | ExprKind::Variable(_)) => return Expr::new(kind, span),
+        kind @ ExprKind::Constant(_) => kind,
+    };
+    let expr = Expr::new(kind, span);
+    if !has_literal_operands(&expr) {
+        return expr;
+    }
+    match Environment::with_config(*config).eval(&expr) {
+        Ok(Value::Int(i)) => Expr::new(ExprKind::Integer(i, expr.preferred_radix()), span),
+        // Infinities and NaN have no literal syntax, so they stay unfolded.
+        Ok(Value::Float(f)) if f.is_finite() => Expr::new(ExprKind::Float(f), span),
+        Ok(Value::Bool(b)) => Expr::new(ExprKind::Bool(b), span),
+        _ => expr,
+    }
+}
+
+/// Folds a boxed operand, reusing its allocation.
+fn fold_boxed(mut expr: Box<Expr>, config: &EvalConfig) -> Box<Expr> {
+    *expr = fold(*expr, config);
+    expr
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    matches!(
+        expr.kind,
+        ExprKind::Integer(..) | ExprKind::Float(_) | ExprKind::Bool(_)
+    )
+}
+
+/// Whether all direct operands of `expr` are literals, so it can be evaluated on its own.
+fn has_literal_operands(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::UnaryMinus(operand)
+        | ExprKind::Not(operand)
+        | ExprKind::Factorial(operand)
+        | ExprKind::Percent(operand) => is_literal(operand),
+        ExprKind::BinOp { lhs, rhs, .. } => is_literal(lhs) && is_literal(rhs),
+        // A range is only evaluated as the argument of `sum` or `product`.
+        ExprKind::Call { args, .. } => args.iter().all(|arg| match &arg.kind {
+            ExprKind::Range { start, end } => is_literal(start) && is_literal(end),
+            _ => is_literal(arg),
+        }),
+        ExprKind::Constant(_) => true,
+        ExprKind::Integer(..) | ExprKind::Float(_) | ExprKind::Bool(_) | ExprKind::Variable(_) => {
+            false
+        }
+        ExprKind::Conditional { .. }
+        | ExprKind::Assign { .. }
+        | ExprKind::Let { .. }
+        | ExprKind::Range { .. } => false,
+    }
+}
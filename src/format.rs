@@ -0,0 +1,137 @@
+use crate::int;
+use crate::{Expr, ExprVisitor, Int, Value};
+
+/// Floats at least this large in magnitude are printed in scientific notation.
+const SCIENTIFIC_THRESHOLD: f64 = 1e15;
+
+/// The base in which integer results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Radix {
+    #[default]
+    Decimal,
+    Hexadecimal,
+    Octal,
+    Binary,
+}
+
+impl Radix {
+    pub(crate) fn base(self) -> u32 {
+        match self {
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+            Radix::Octal => 8,
+            Radix::Binary => 2,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Radix::Decimal => "decimal",
+            Radix::Hexadecimal => "hexadecimal",
+            Radix::Octal => "octal",
+            Radix::Binary => "binary",
+        }
+    }
+}
+
+/// Formats a result for printing, with integers in the given `radix`.
+///
+/// Integers in another radix than decimal get the same prefix as the literals, so `255` in
+/// [`Radix::Hexadecimal`] is `0xff` and `-5` in [`Radix::Binary`] is `-0b101`.
+/// Floats of magnitude `1e15` and above are written in scientific notation, e.g. `1.5e20`.
+/// Floats can only be printed in decimal, so any other radix is an error for them.
+/// Booleans are printed the same in every radix.
+pub fn format_result(value: &Value, radix: Radix) -> Result<String, String> {
+    match value {
+        Value::Int(i) => Ok(format_int(i, radix)),
+        Value::Float(_) if radix != Radix::Decimal => Err(format!(
+            "cannot print the float {} in {}",
+            value,
+            radix.name()
+        )),
+        Value::Float(x) if x.is_finite() && x.abs() >= SCIENTIFIC_THRESHOLD => {
+            Ok(format!("{:e}", x))
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Formats a result in decimal like [`format_result`], with `separator` between every three digits
+/// of the integer part, e.g. `1,000,000` or `-1_234.5678`.
+///
+/// The fractional part and the exponent of floats are left as they are, and so are booleans.
+pub fn format_grouped(value: &Value, separator: char) -> String {
+    // Decimal never fails.
+    let number = format_result(value, Radix::Decimal).unwrap();
+    let (sign, unsigned) = match number.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", number.as_str()),
+    };
+    let digits = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    let (integer, rest) = unsigned.split_at(digits);
+    let mut grouped = sign.to_owned();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (digits - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped + rest
+}
+
+/// Writes an integer with the prefix for `radix`, like a literal.
+pub(crate) fn format_int(i: &Int, radix: Radix) -> String {
+    let sign = if int::is_negative(i) { "-" } else { "" };
+    let magnitude = int::magnitude(i);
+    match radix {
+        Radix::Decimal => i.to_string(),
+        Radix::Hexadecimal => format!("{}0x{:x}", sign, magnitude),
+        Radix::Octal => format!("{}0o{:o}", sign, magnitude),
+        Radix::Binary => format!("{}0b{:b}", sign, magnitude),
+    }
+}
+
+impl Expr {
+    /// The radix to print an integer result of this expression in, based on its literals.
+    ///
+    /// This is hexadecimal if any literal is written in hexadecimal, so `0xFF + 1` gives `0x100`,
+    /// and otherwise binary or octal if any literal is written that way, in that order.
+    /// Without such literals it is decimal, which includes expressions that only use variables.
+    pub fn preferred_radix(&self) -> Radix {
+        struct Literals {
+            hexadecimal: bool,
+            binary: bool,
+            octal: bool,
+        }
+
+        impl<'a> ExprVisitor<'a> for Literals {
+            fn visit_integer(&mut self, _value: Int, radix: Radix) {
+                match radix {
+                    Radix::Hexadecimal => self.hexadecimal = true,
+                    Radix::Binary => self.binary = true,
+                    Radix::Octal => self.octal = true,
+                    Radix::Decimal => {}
+                }
+            }
+        }
+
+        let mut literals = Literals {
+            hexadecimal: false,
+            binary: false,
+            octal: false,
+        };
+        literals.visit_expr(self);
+        if literals.hexadecimal {
+            Radix::Hexadecimal
+        } else if literals.binary {
+            Radix::Binary
+        } else if literals.octal {
+            Radix::Octal
+        } else {
+            Radix::Decimal
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Built-in functions that can be called from expressions, e.g. `max(1, 5)`.
+
+use crate::eval::apply;
+use crate::int;
+use crate::{EvalConfig, EvalErrorKind, Int, Op, Value};
+
+/// Calls the built-in function `name` with already evaluated arguments.
+///
+/// Like the arithmetic operators, these keep integer arguments as integers where possible:
+/// `abs`, `min`, `max`, `pow`, `diff` and `clamp` only produce a float if one of their arguments is a float,
+/// while `sqrt` and `percent` always return a float. `min` and `max` take one or more arguments, e.g. `max(1, 7, 3)`,
+/// and the other functions an exact number.
+///
+/// `percent(part, whole)` is `part / whole * 100`, `diff(a, b)` is `abs(a - b)`, and `clamp(x, lo, hi)`
+/// is `x` limited to the range from `lo` to `hi`, which fails if `lo` is greater than `hi`.
+///
+/// Function names are case-insensitive, so `SQRT(2)` and `Max(1, 2)` work too.
+pub(crate) fn call(
+    name: &str,
+    args: &[Value],
+    config: &EvalConfig,
+) -> Result<Value, EvalErrorKind> {
+    let lowercase = name.to_ascii_lowercase();
+    let (name, arity, variadic) = match lowercase.as_str() {
+        name @ ("abs" | "sqrt") => (name, 1, false),
+        name @ ("min" | "max") => (name, 1, true),
+        name @ ("pow" | "percent" | "diff") => (name, 2, false),
+        name @ "clamp" => (name, 3, false),
+        name @ ("sum" | "product") => return Err(EvalErrorKind::ExpectedRange(name.to_owned())),
+        _ => return Err(EvalErrorKind::UnknownFunction(name.to_owned())),
+    };
+    if args.len() < arity || !variadic && args.len() > arity {
+        return Err(EvalErrorKind::ArityMismatch {
+            function: name.to_owned(),
+            expected: arity,
+            variadic,
+            found: args.len(),
+        });
+    }
+
+    match (name, args) {
+        ("abs", [Value::Int(i)]) => int::abs(i)
+            .map(Value::Int)
+            .ok_or(EvalErrorKind::Overflow("abs")),
+        ("abs", [x]) => Ok(Value::Float(x.as_f64()?.abs())),
+        ("sqrt", [x]) => Ok(Value::Float(x.as_f64()?.sqrt())),
+        ("min" | "max", [Value::Bool(_), ..]) => Err(args[0].mismatch("a number")),
+        ("min" | "max", [first, rest @ ..]) => rest.iter().try_fold(first.clone(), |acc, arg| {
+            extremum(name == "max", acc, arg.clone())
+        }),
+        ("pow", [base, exponent]) => apply(base.clone(), &Op::Power, exponent.clone(), config),
+        ("percent", [part, whole]) => {
+            let (part, whole) = (part.as_f64()?, whole.as_f64()?);
+            if whole == 0.0 {
+                return Err(EvalErrorKind::DivisionByZero);
+            }
+            Ok(Value::Float(part / whole * 100.0))
+        }
+        ("diff", [a, b]) => call(
+            "abs",
+            &[apply(a.clone(), &Op::Subtract, b.clone(), config)?],
+            config,
+        ),
+        ("clamp", [x, lo, hi]) => {
+            if apply(lo.clone(), &Op::Greater, hi.clone(), config)? == Value::Bool(true) {
+                return Err(EvalErrorKind::InvalidArguments {
+                    function: name.to_owned(),
+                    reason: "the lower bound is greater than the upper bound",
+                });
+            }
+            extremum(true, extremum(false, x.clone(), hi.clone())?, lo.clone())
+        }
+        _ => unreachable!("arity of {} was checked above", name),
+    }
+}
+
+/// The larger of two numbers if `max`, otherwise the smaller one, which is only a float if either of them is.
+fn extremum(max: bool, a: Value, b: Value) -> Result<Value, EvalErrorKind> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(if max { a.max(b) } else { a.min(b) })),
+        (a, b) => {
+            let (a, b) = (a.as_f64()?, b.as_f64()?);
+            Ok(Value::Float(if max { a.max(b) } else { a.min(b) }))
+        }
+    }
+}
+
+/// Calls the built-in function `name` with the range `start..end`, which includes `end`.
+///
+/// `sum` adds up the integers of the range and `product` multiplies them, with the same overflow checks
+/// as `+` and `*`. A range whose end is less than its start is empty, so its sum is 0 and its product 1.
+/// Ranges with more than [`EvalConfig::max_range`] elements are rejected before any of them is computed.
+/// Otherwise the number of elements is passed to `count` first, which can fail to stop the evaluation,
+/// so a range that is too large is reported as such even if it would also take too many operations.
+pub(crate) fn aggregate(
+    name: &str,
+    start: Int,
+    end: Int,
+    config: &EvalConfig,
+    count: impl FnOnce(u64) -> Result<(), EvalErrorKind>,
+) -> Result<Value, EvalErrorKind> {
+    let (op, identity) = match name.to_ascii_lowercase().as_str() {
+        "sum" => (Op::Add, Int::from(0u8)),
+        "product" => (Op::Multiply, Int::from(1u8)),
+        "abs" | "sqrt" | "min" | "max" | "pow" | "percent" | "diff" | "clamp" => {
+            return Err(EvalErrorKind::MisplacedRange)
+        }
+        _ => return Err(EvalErrorKind::UnknownFunction(name.to_owned())),
+    };
+    let len = int::range_len(&start, &end);
+    if len > config.max_range {
+        return Err(EvalErrorKind::RangeTooLarge {
+            max: config.max_range,
+        });
+    }
+    count(len)?;
+    (0..len).try_fold(Value::Int(identity), |acc, offset| {
+        apply(acc, &op, Value::Int(int::offset(&start, offset)), config)
+    })
+}
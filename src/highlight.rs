@@ -0,0 +1,99 @@
+//! Syntax highlighting of input with ANSI escape sequences.
+
+use crate::{ParseConfig, Rule};
+
+const NUMBER: &str = "\x1b[36m";
+const OPERATOR: &str = "\x1b[33m";
+const PARENTHESIS: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Colors the numbers, operators and parentheses of a line of statements for a terminal, see [`ParseConfig::highlight`].
+pub fn highlight(input: &str) -> String {
+    ParseConfig::default().highlight(input)
+}
+
+impl ParseConfig {
+    /// Wraps the numbers of `input` in cyan, its operators in yellow and its parentheses in dim ANSI escape sequences.
+    ///
+    /// The tokens are found with [`ParseConfig::tokens`], so input that does not parse is returned unchanged.
+    /// Everything else, including names, whitespace and comments, is copied as it is.
+    pub fn highlight(&self, input: &str) -> String {
+        let tokens = match self.tokens(input) {
+            Ok(tokens) => tokens,
+            Err(_) => return input.to_owned(),
+        };
+        let mut highlighted = String::with_capacity(input.len());
+        let mut end = 0;
+        for token in tokens {
+            let color = match token_color(token.rule) {
+                Some(color) => color,
+                None => continue,
+            };
+            let span = token.span;
+            highlight_gap(&input[end..span.start], &mut highlighted);
+            paint(&input[span.start..span.end], color, &mut highlighted);
+            end = span.end;
+        }
+        highlight_gap(&input[end..], &mut highlighted);
+        highlighted
+    }
+}
+
+/// The color of a token, or `None` for tokens that are not highlighted, like names.
+fn token_color(rule: Rule) -> Option<&'static str> {
+    match rule {
+        Rule::integer | Rule::decimal | Rule::hexadecimal | Rule::octal | Rule::binary => {
+            Some(NUMBER)
+        }
+        Rule::add
+        | Rule::subtract
+        | Rule::multiply
+        | Rule::divide
+        | Rule::floor_divide
+        | Rule::modulo
+        | Rule::power
+        | Rule::less_equal
+        | Rule::greater_equal
+        | Rule::equal
+        | Rule::not_equal
+        | Rule::less
+        | Rule::greater
+        | Rule::and
+        | Rule::or
+        | Rule::bit_and
+        | Rule::bit_or
+        | Rule::bit_xor
+        | Rule::shift_left
+        | Rule::shift_right
+        | Rule::factorial
+        | Rule::percent => Some(OPERATOR),
+        _ => None,
+    }
+}
+
+/// Highlights the text between two tokens, which holds no numbers but may hold the parentheses and
+/// prefix operators that the grammar matches as plain strings, along with whitespace and comments.
+fn highlight_gap(mut gap: &str, highlighted: &mut String) {
+    while let Some(c) = gap.chars().next() {
+        let len = if gap.starts_with('#') {
+            gap.find('\n').unwrap_or(gap.len())
+        } else if let Some(comment) = gap.strip_prefix("/*") {
+            comment.find("*/").map_or(gap.len(), |end| end + 4)
+        } else {
+            c.len_utf8()
+        };
+        let (text, rest) = gap.split_at(len);
+        match c {
+            '(' | ')' => paint(text, PARENTHESIS, highlighted),
+            '-' | '!' | '?' | ':' | '=' => paint(text, OPERATOR, highlighted),
+            _ => highlighted.push_str(text),
+        }
+        gap = rest;
+    }
+}
+
+fn paint(text: &str, color: &str, highlighted: &mut String) {
+    highlighted.push_str(color);
+    highlighted.push_str(text);
+    highlighted.push_str(RESET);
+}
@@ -0,0 +1,154 @@
+//! The representation of integers, which is `i64` by default and `BigInt` with the `bignum` feature.
+//!
+//! Arithmetic on integers goes through [`Number`](crate::Number); these are the remaining operations
+//! that depend on the representation.
+
+#[cfg(feature = "bignum")]
+use num_traits::{Signed, ToPrimitive};
+
+/// The integer type used for literals and integer arithmetic, whose operations fail on overflow.
+#[cfg(not(feature = "bignum"))]
+pub type Int = i64;
+/// The integer type used for literals and integer arithmetic, which cannot overflow.
+#[cfg(feature = "bignum")]
+pub type Int = num_bigint::BigInt;
+
+/// The absolute value of an [`Int`], which can be printed in any radix.
+#[cfg(not(feature = "bignum"))]
+pub(crate) type Magnitude = u64;
+#[cfg(feature = "bignum")]
+pub(crate) type Magnitude = num_bigint::BigUint;
+
+#[cfg(not(feature = "bignum"))]
+mod repr {
+    use super::{Int, Magnitude};
+
+    /// Parses digits in `radix` without a prefix or sign, failing if they do not fit in an [`Int`].
+    pub(crate) fn parse(digits: &str, radix: u32) -> Option<Int> {
+        Int::from_str_radix(digits, radix).ok()
+    }
+
+    pub(crate) fn is_negative(i: &Int) -> bool {
+        *i < 0
+    }
+
+    /// Whether the magnitude of `i` does not fit in an [`Int`], so that a literal with a `-` in front cannot write it.
+    pub(crate) fn is_min(i: &Int) -> bool {
+        *i == Int::MIN
+    }
+
+    /// The absolute value of `i`, which never overflows.
+    pub(crate) fn magnitude(i: &Int) -> Magnitude {
+        i.unsigned_abs()
+    }
+
+    /// The nearest `f64` to `i`.
+    pub(crate) fn to_f64(i: &Int) -> f64 {
+        *i as f64
+    }
+
+    /// `i` as a 64-bit integer, if it fits.
+    pub(crate) fn to_i64(i: &Int) -> Option<i64> {
+        Some(*i)
+    }
+
+    /// The absolute value of `i`, failing if it does not fit in an [`Int`].
+    pub(crate) fn abs(i: &Int) -> Option<Int> {
+        i.checked_abs()
+    }
+
+    /// The product of the integers from 1 to `n`, for a non-negative `n`, failing if it does not fit in an [`Int`].
+    pub(crate) fn factorial(n: &Int) -> Option<Int> {
+        (2..=*n).try_fold(1, |acc: Int, i| acc.checked_mul(i))
+    }
+
+    /// `i` shifted left by `amount` bits, failing for negative amounts and amounts of at least the width of an [`Int`].
+    ///
+    /// Negative amounts fail the conversion, and `checked_shl` rejects amounts that are too large.
+    pub(crate) fn shift_left(i: &Int, amount: &Int) -> Option<Int> {
+        u32::try_from(*amount)
+            .ok()
+            .and_then(|amount| i.checked_shl(amount))
+    }
+
+    /// `i` shifted right by `amount` bits with its sign extended, failing like [`shift_left`].
+    pub(crate) fn shift_right(i: &Int, amount: &Int) -> Option<Int> {
+        u32::try_from(*amount)
+            .ok()
+            .and_then(|amount| i.checked_shr(amount))
+    }
+
+    /// The number of integers from `start` to `end` inclusive, or `u64::MAX` if there are more.
+    pub(crate) fn range_len(start: &Int, end: &Int) -> u64 {
+        // Computed in i128, since the length of a range of `Int`s can exceed `Int::MAX`.
+        (*end as i128 - *start as i128 + 1).clamp(0, u64::MAX as i128) as u64
+    }
+
+    /// The integer `offset` after `start`, which must be at most the end of a range starting there.
+    pub(crate) fn offset(start: &Int, offset: u64) -> Int {
+        (*start as i128 + offset as i128) as Int
+    }
+}
+
+#[cfg(feature = "bignum")]
+mod repr {
+    use super::*;
+
+    pub(crate) fn parse(digits: &str, radix: u32) -> Option<Int> {
+        Int::parse_bytes(digits.as_bytes(), radix)
+    }
+
+    pub(crate) fn is_negative(i: &Int) -> bool {
+        i.is_negative()
+    }
+
+    pub(crate) fn is_min(_: &Int) -> bool {
+        false
+    }
+
+    pub(crate) fn magnitude(i: &Int) -> Magnitude {
+        i.magnitude().clone()
+    }
+
+    /// Integers beyond the range of `f64` become infinite.
+    pub(crate) fn to_f64(i: &Int) -> f64 {
+        i.to_f64().unwrap()
+    }
+
+    pub(crate) fn to_i64(i: &Int) -> Option<i64> {
+        i.to_i64()
+    }
+
+    pub(crate) fn abs(i: &Int) -> Option<Int> {
+        Some(Signed::abs(i))
+    }
+
+    /// Only fails if `n` does not fit in a `u64`, which would take far too long to compute anyway.
+    pub(crate) fn factorial(n: &Int) -> Option<Int> {
+        Some((2..=n.to_u64()?).product())
+    }
+
+    /// Shifting by more than `u32::MAX` bits would not leave a number that fits in memory.
+    pub(crate) fn shift_left(i: &Int, amount: &Int) -> Option<Int> {
+        u32::try_from(amount).ok().map(|amount| i << amount)
+    }
+
+    pub(crate) fn shift_right(i: &Int, amount: &Int) -> Option<Int> {
+        u32::try_from(amount).ok().map(|amount| i >> amount)
+    }
+
+    pub(crate) fn range_len(start: &Int, end: &Int) -> u64 {
+        let len: Int = end - start + 1u8;
+        if len.is_negative() {
+            0
+        } else {
+            len.to_u64().unwrap_or(u64::MAX)
+        }
+    }
+
+    pub(crate) fn offset(start: &Int, offset: u64) -> Int {
+        start + offset
+    }
+}
+
+pub(crate) use repr::*;
@@ -0,0 +1,925 @@
+//! A calculator for integer and floating-point expressions, parsed with pest's Pratt parser.
+//!
+//! Input is first parsed into pest [`Pairs`] with [`CalculatorParser`], then turned into an [`Expr`]
+//! tree by [`parse_expr`], which can finally be evaluated with [`eval`].
+//! [`evaluate`] runs this whole pipeline in one call.
+//!
+//! With the `bignum` feature, [`Int`] is an arbitrary-precision integer instead of an `i64`, so integer
+//! results never overflow. Integers and [`Value`] are then no longer `Copy`.
+
+// Integers are only `Copy` without the `bignum` feature, so code shared by both has to clone them.
+#![cfg_attr(not(feature = "bignum"), allow(clippy::clone_on_copy))]
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+
+use pest::error::{ErrorVariant, InputLocation};
+use pest::iterators::{Pair, Pairs};
+use pest::pratt_parser::PrattParser;
+use pest::Parser;
+
+mod canonical;
+mod display;
+mod eval;
+mod fold;
+mod format;
+mod functions;
+mod highlight;
+mod int;
+mod lines;
+mod memo;
+mod number;
+mod rpn;
+mod simplify;
+mod steps;
+mod tokens;
+mod tree;
+mod visit;
+
+pub use crate::canonical::canonicalize;
+pub use crate::eval::{
+    eval, EnabledOperators, Environment, EvalConfig, EvalError, EvalErrorKind, ModuloMode, Value,
+};
+pub use crate::fold::fold_constants;
+pub use crate::format::{format_grouped, format_result, Radix};
+pub use crate::highlight::highlight;
+pub use crate::int::Int;
+pub use crate::lines::{
+    eval_lines, read_entry, Entry, EvalLines, EvaluatedLine, EvaluatedStatement,
+};
+pub use crate::memo::eval_memoized;
+pub use crate::number::{eval_as, Number};
+pub use crate::rpn::{eval_rpn, to_rpn, try_to_rpn, Token};
+pub use crate::simplify::simplify;
+pub use crate::steps::eval_steps;
+pub use crate::tokens::{tokens, SpannedToken};
+pub use crate::tree::render_tree;
+pub use crate::visit::{walk, ExprVisitor};
+
+#[derive(pest_derive::Parser)]
+#[grammar = "calculator.pest"]
+pub struct CalculatorParser;
+
+lazy_static::lazy_static! {
+    // One level per precedence of `Op`, loosest first.
+    static ref PRATT_PARSER: PrattParser<Rule> = {
+        use pest::pratt_parser::{Assoc, Op as Operator};
+
+        let max = Op::ALL.iter().map(Op::precedence).max().unwrap();
+        (1..=max).fold(PrattParser::new(), |parser, precedence| {
+            let level = Op::ALL.iter()
+                .filter(|op| op.precedence() == precedence)
+                .flat_map(|op| {
+                    let assoc = match op.associativity() {
+                        Associativity::Left => Assoc::Left,
+                        Associativity::Right => Assoc::Right,
+                    };
+                    op.rules().iter().map(move |&rule| Operator::infix(rule, assoc))
+                })
+                .reduce(|level, operator| level | operator)
+                .unwrap();
+            parser.op(level)
+        })
+    };
+}
+
+/// A byte range in the input that an expression was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+impl From<pest::Span<'_>> for Span {
+    fn from(span: pest::Span) -> Self {
+        Span::new(span.start(), span.end())
+    }
+}
+
+/// A node of the expression tree, together with the part of the input it was parsed from.
+///
+/// `==` compares spans too; use [`Expr::structurally_eq`] to compare trees parsed from different inputs.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+
+    /// Whether both trees have the same shape and leaves, ignoring their spans.
+    ///
+    /// Parsing the [`Display`](fmt::Display) output of an expression gives a structurally equal tree.
+    pub fn structurally_eq(&self, other: &Expr) -> bool {
+        use ExprKind::*;
+
+        let all_eq = |a: &[Expr], b: &[Expr]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.structurally_eq(b))
+        };
+        match (&self.kind, &other.kind) {
+            (Integer(a, a_radix), Integer(b, b_radix)) => a == b && a_radix == b_radix,
+            (Float(a), Float(b)) => a == b,
+            (Bool(a), Bool(b)) => a == b,
+            (Constant(a), Constant(b)) => a == b,
+            (Variable(a), Variable(b)) => a == b,
+            (Call { name, args }, Call { name: other_name, args: other_args }) => {
+                name == other_name && all_eq(args, other_args)
+            }
+            (UnaryMinus(a), UnaryMinus(b))
+            | (Not(a), Not(b))
+            | (Factorial(a), Factorial(b))
+            | (Percent(a), Percent(b)) => a.structurally_eq(b),
+            (BinOp { lhs, op, rhs }, BinOp { lhs: other_lhs, op: other_op, rhs: other_rhs }) => {
+                op == other_op && lhs.structurally_eq(other_lhs) && rhs.structurally_eq(other_rhs)
+            }
+            (
+                Conditional { cond, then, otherwise },
+                Conditional { cond: other_cond, then: other_then, otherwise: other_otherwise },
+            ) => {
+                cond.structurally_eq(other_cond)
+                    && then.structurally_eq(other_then)
+                    && otherwise.structurally_eq(other_otherwise)
+            }
+            (Assign { name, value }, Assign { name: other_name, value: other_value }) => {
+                name == other_name && value.structurally_eq(other_value)
+            }
+            (
+                Let { name, value, body },
+                Let { name: other_name, value: other_value, body: other_body },
+            ) => {
+                name == other_name
+                    && value.structurally_eq(other_value)
+                    && body.structurally_eq(other_body)
+            }
+            (Range { start, end }, Range { start: other_start, end: other_end }) => {
+                start.structurally_eq(other_start) && end.structurally_eq(other_end)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Expressions built by hand rather than parsed get an empty span.
+impl From<ExprKind> for Expr {
+    fn from(kind: ExprKind) -> Self {
+        Expr::new(kind, Span::default())
+    }
+}
+
+/// Only shows the tree itself; spans would make nested trees very hard to read.
+impl fmt::Debug for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExprKind {
+    /// An integer literal, and the radix it was written in.
+    Integer(Int, Radix),
+    Float(f64),
+    Bool(bool),
+    Constant(Constant),
+    Variable(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    UnaryMinus(Box<Expr>),
+    Not(Box<Expr>),
+    Factorial(Box<Expr>),
+    /// `x%`, which is `x / 100` as a float.
+    Percent(Box<Expr>),
+    BinOp {
+        lhs: Box<Expr>,
+        op: Op,
+        rhs: Box<Expr>,
+    },
+    /// `cond ? then : otherwise`, which only evaluates the branch that is taken.
+    Conditional {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
+    /// Assigns to a variable, evaluating to the assigned value.
+    Assign {
+        name: String,
+        value: Box<Expr>,
+    },
+    /// `let name = value in body`, which binds `name` only while evaluating `body`.
+    Let {
+        name: String,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    /// `start..end`, the integers from `start` to `end` inclusive, which can only be the argument of `sum` or `product`.
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+    },
+}
+
+/// A named mathematical constant.
+///
+/// The names `pi` and `e` are reserved for these constants in any case, so neither `pi` nor `PI` can be assigned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Constant {
+    Pi,
+    E,
+}
+
+impl Constant {
+    /// Looks up a constant by name, ignoring case, so `Pi` and `PI` are `pi` too.
+    pub fn from_name(name: &str) -> Option<Constant> {
+        match name.to_ascii_lowercase().as_str() {
+            "pi" => Some(Constant::Pi),
+            "e" => Some(Constant::E),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Constant::Pi => "pi",
+            Constant::E => "e",
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        match self {
+            Constant::Pi => std::f64::consts::PI,
+            Constant::E => std::f64::consts::E,
+        }
+    }
+}
+
+/// The nesting limit used by [`ParseConfig::default`].
+///
+/// Every level of nesting costs stack space in pest and while building the tree, and this limit
+/// keeps well clear of the 2 MiB that a thread gets by default, even in debug builds.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Settings for parsing input into expressions.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseConfig {
+    /// How deeply expressions may nest before parsing fails with [`CalcError::TooDeep`].
+    ///
+    /// Parentheses, function arguments, prefix operators, assignments and conditional branches
+    /// each add one level.
+    pub max_depth: usize,
+    /// Whether an operand directly followed by a name or a parenthesized expression is multiplied by it,
+    /// so that `2(3 + 4)` is `2 * (3 + 4)` and `3x` is `3 * x`. Off by default.
+    ///
+    /// This binds like `*`, so `2x^2` is `2 * x^2` and `1 / 2x` is `(1 / 2) * x`.
+    /// A name followed by parentheses is still a function call, and a number followed by a number
+    /// or a `-` is not multiplied, so `2 3` is an error and `2 -3` is a subtraction.
+    pub implicit_multiplication: bool,
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        ParseConfig {
+            max_depth: DEFAULT_MAX_DEPTH,
+            implicit_multiplication: false,
+        }
+    }
+}
+
+impl ParseConfig {
+    /// Parses `input` with pest, after checking that it does not nest too deeply for pest to handle.
+    pub fn parse<'i>(&self, rule: Rule, input: &'i str) -> Result<Pairs<'i, Rule>, CalcError> {
+        if nests_deeper_than(input, self.max_depth) {
+            return Err(CalcError::TooDeep(self.max_depth));
+        }
+        let rule = match rule {
+            Rule::equation if self.implicit_multiplication => Rule::implicit_equation,
+            Rule::statement if self.implicit_multiplication => Rule::implicit_statement,
+            Rule::program if self.implicit_multiplication => Rule::implicit_program,
+            rule => rule,
+        };
+        CalculatorParser::parse(rule, input)
+            .map_err(|e| describe_misplaced_operator(e, input).into())
+    }
+
+    /// Builds an expression tree from the pairs of an `expr`, `equation` or `statement`, see [`parse_expr`].
+    pub fn parse_expr(&self, pairs: Pairs<Rule>) -> Result<Expr, CalcError> {
+        build_expr(pairs, self.depth())
+    }
+
+    /// Builds the statements of a `program`, see [`parse_program`].
+    pub fn parse_program(&self, pairs: Pairs<Rule>) -> Result<Vec<Expr>, CalcError> {
+        pairs
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(|pair| build_expr(pair.into_inner(), self.depth()))
+            .collect()
+    }
+
+    fn depth(&self) -> Depth {
+        Depth {
+            current: 0,
+            max: self.max_depth,
+        }
+    }
+}
+
+/// Replaces the error for a binary operator without an operand on one side by a message saying so,
+/// for `+5`, `5 +` and `5 * * 3`.
+///
+/// These are the errors expecting an operand, or an expression at its start, as `5 +` does at its end
+/// or `+5` before the `+`. The location is kept, so the error can still be shown and continued like any other.
+fn describe_misplaced_operator(
+    mut e: pest::error::Error<Rule>,
+    input: &str,
+) -> pest::error::Error<Rule> {
+    let pos = match e.location {
+        InputLocation::Pos(pos) => pos,
+        InputLocation::Span(_) => return e,
+    };
+    let rest = input[pos..].trim_start();
+    let starts_with_operator = rest.starts_with(|c| "+*/%^<>=&|~".contains(c));
+    let message = match &e.variant {
+        ErrorVariant::ParsingError { positives, .. } => match positives.as_slice() {
+            [Rule::postfix] if rest.is_empty() => "expression ends with an operator",
+            [Rule::postfix] if starts_with_operator => "two operators in a row",
+            [Rule::expr] if starts_with_operator => "expression starts with a binary operator",
+            _ => return e,
+        },
+        ErrorVariant::CustomError { .. } => return e,
+    };
+    e.variant = ErrorVariant::CustomError {
+        message: message.to_owned(),
+    };
+    e
+}
+
+/// Whether `input` might nest more than `max_depth` levels deep, counted like [`ParseConfig::max_depth`].
+///
+/// This only looks at the characters, so it overestimates in some cases, e.g. for parentheses in comments.
+fn nests_deeper_than(input: &str, max_depth: usize) -> bool {
+    let bytes = input.as_bytes();
+    // The levels opened by each enclosing parenthesis, which all end at its closing parenthesis,
+    // and the prefix operators of the power chain that the parenthesis is an operand of.
+    let mut open = Vec::new();
+    let mut depth = 0;
+    // Prefix operators in front of the next operand.
+    let mut prefix = 0;
+    // Prefix operators whose operand is followed by `^`. A unary minus extends over the `^` chain after
+    // its operand, so `2 ^ -2 ^ -2` nests once per `-` until an operator other than `^` ends the chain.
+    let mut chain = 0;
+    let mut expect_operand = true;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'(' => {
+                // The prefix operators stay open after the parenthesis, as long as its power chain goes on.
+                open.push((1, chain + prefix));
+                depth += prefix + 1;
+                prefix = 0;
+                chain = 0;
+                expect_operand = true;
+            }
+            b')' => {
+                let (levels, outer_chain) = open.pop().unwrap_or((0, 0));
+                depth -= levels + chain;
+                chain = outer_chain;
+                expect_operand = false;
+            }
+            b'-' | b'!' if expect_operand => prefix += 1,
+            // A factorial, or the start of `!=`.
+            b'!' => {}
+            b'=' if !matches!(bytes.get(i + 1), Some(b'='))
+                && !matches!(i.checked_sub(1).map(|j| bytes[j]), Some(b'=' | b'<' | b'>' | b'!')) =>
+            {
+                depth = depth + 1 - chain;
+                chain = 0;
+                if let Some((levels, _)) = open.last_mut() {
+                    *levels += 1;
+                }
+                expect_operand = true;
+            }
+            b'?' => {
+                depth = depth + 1 - chain;
+                chain = 0;
+                if let Some((levels, _)) = open.last_mut() {
+                    *levels += 1;
+                }
+                expect_operand = true;
+            }
+            b if b.is_ascii_alphanumeric() || b == b'_' || b == b'.' => {
+                expect_operand = false;
+            }
+            b if b.is_ascii_whitespace() => {}
+            b'^' => expect_operand = true,
+            _ => {
+                depth -= chain;
+                chain = 0;
+                expect_operand = true;
+            }
+        }
+        if depth + prefix > max_depth {
+            return true;
+        }
+        if !expect_operand {
+            depth += prefix;
+            chain += prefix;
+            prefix = 0;
+        }
+    }
+    false
+}
+
+/// How deeply the expression being built is nested, and how deeply it may be nested.
+#[derive(Clone, Copy)]
+struct Depth {
+    current: usize,
+    max: usize,
+}
+
+impl Depth {
+    /// The depth one level further in, failing if that is too deep.
+    fn nested(self) -> Result<Depth, CalcError> {
+        if self.current >= self.max {
+            return Err(CalcError::TooDeep(self.max));
+        }
+        Ok(Depth {
+            current: self.current + 1,
+            ..self
+        })
+    }
+}
+
+/// Builds an expression from the pairs of a `statement`, which may be an assignment.
+///
+/// Fails if a literal is too large to be represented, or if the statement assigns to a constant.
+pub fn parse_statement(pairs: Pairs<Rule>) -> Result<Expr, CalcError> {
+    parse_expr(pairs)
+}
+
+/// Builds the statements of a `program`, in the order they appear in the input.
+pub fn parse_program(pairs: Pairs<Rule>) -> Result<Vec<Expr>, CalcError> {
+    ParseConfig::default().parse_program(pairs)
+}
+
+fn parse_assignment(pair: Pair<Rule>, depth: Depth) -> Result<ExprKind, CalcError> {
+    let mut inner = pair.into_inner();
+    let name = parse_binding_name(inner.next().unwrap())?;
+    let value = Box::new(build_expr(inner, depth.nested()?)?);
+    Ok(ExprKind::Assign { name, value })
+}
+
+fn parse_let(pair: Pair<Rule>, depth: Depth) -> Result<ExprKind, CalcError> {
+    let mut inner = pair.into_inner();
+    let name = parse_binding_name(inner.next().unwrap())?;
+    let value = Box::new(build_expr(inner.next().unwrap().into_inner(), depth.nested()?)?);
+    let body = Box::new(build_expr(inner.next().unwrap().into_inner(), depth.nested()?)?);
+    Ok(ExprKind::Let { name, value, body })
+}
+
+/// The name that an assignment or `let` binds, which must not be reserved.
+fn parse_binding_name(identifier: Pair<Rule>) -> Result<String, CalcError> {
+    let name = identifier.as_str().to_owned();
+    if Constant::from_name(&name).is_some() || name == "true" || name == "false" {
+        return Err(CalcError::ReservedName(name));
+    }
+    Ok(name)
+}
+
+/// Builds an expression tree from the pairs of an `expr` or `equation`.
+///
+/// Fails if a literal is too large to be represented, or if the expression nests more than
+/// [`DEFAULT_MAX_DEPTH`] levels deep. Parentheses only group, so `(5)` gives the same tree as `5`.
+///
+/// # Panics
+///
+/// Panics if `pairs` did not come from parsing one of those rules. Every input that pest accepts
+/// for them can be built, and inputs such as `()` are already rejected by pest.
+pub fn parse_expr(pairs: Pairs<Rule>) -> Result<Expr, CalcError> {
+    ParseConfig::default().parse_expr(pairs)
+}
+
+fn build_expr(mut pairs: Pairs<Rule>, depth: Depth) -> Result<Expr, CalcError> {
+    let first = pairs.next().unwrap();
+    // Handled here rather than in `parse_primary`, which needs a lot more stack space per call.
+    let cond = match first.as_rule() {
+        Rule::expr => build_expr(first.into_inner(), depth)?,
+        Rule::operation => parse_operation(first.into_inner(), depth)?,
+        Rule::postfix => parse_postfix(first, depth)?,
+        _ => parse_primary(first, depth)?,
+    };
+    // The branches of a conditional follow its condition, anything else is the end of the input.
+    match (pairs.next(), pairs.next()) {
+        (Some(then), Some(otherwise)) if then.as_rule() == Rule::expr => {
+            let then = build_expr(then.into_inner(), depth.nested()?)?;
+            let otherwise = build_expr(otherwise.into_inner(), depth.nested()?)?;
+            let span = cond.span.to(otherwise.span);
+            Ok(Expr::new(
+                ExprKind::Conditional {
+                    cond: Box::new(cond),
+                    then: Box::new(then),
+                    otherwise: Box::new(otherwise),
+                },
+                span,
+            ))
+        }
+        _ => Ok(cond),
+    }
+}
+
+/// Builds an expression tree from the pairs of an `operation`.
+fn parse_operation(pairs: Pairs<Rule>, depth: Depth) -> Result<Expr, CalcError> {
+    PRATT_PARSER
+        .map_primary(|pair| parse_postfix(pair, depth))
+        .map_infix(|lhs: Result<Expr, CalcError>, op: Pair<Rule>, rhs: Result<Expr, CalcError>| {
+            let op = match op.as_rule() {
+                Rule::add => Op::Add,
+                Rule::subtract => Op::Subtract,
+                Rule::multiply | Rule::implicit_multiply => Op::Multiply,
+                Rule::divide => Op::Divide,
+                Rule::floor_divide => Op::FloorDivide,
+                Rule::modulo => Op::Modulo,
+                Rule::power => Op::Power,
+                Rule::less => Op::Less,
+                Rule::less_equal => Op::LessEqual,
+                Rule::greater => Op::Greater,
+                Rule::greater_equal => Op::GreaterEqual,
+                Rule::equal => Op::Equal,
+                Rule::not_equal => Op::NotEqual,
+                Rule::and => Op::And,
+                Rule::or => Op::Or,
+                Rule::bit_and => Op::BitAnd,
+                Rule::bit_or => Op::BitOr,
+                Rule::bit_xor => Op::BitXor,
+                Rule::shift_left => Op::ShiftLeft,
+                Rule::shift_right => Op::ShiftRight,
+                rule => unreachable!("parse_expr expected infix operation, found {:?}", rule),
+            };
+            let (lhs, rhs) = (lhs?, rhs?);
+            let span = lhs.span.to(rhs.span);
+            Ok(Expr::new(
+                ExprKind::BinOp {
+                    lhs: Box::new(lhs),
+                    op,
+                    rhs: Box::new(rhs),
+                },
+                span,
+            ))
+        })
+        .parse(pairs)
+}
+
+/// Builds a `postfix`, which is a primary followed by any number of postfix operators.
+fn parse_postfix(pair: Pair<Rule>, depth: Depth) -> Result<Expr, CalcError> {
+    let mut pairs = pair.into_inner();
+    let operand = parse_primary(pairs.next().unwrap(), depth)?;
+    Ok(pairs.fold(operand, |operand, op| {
+        let span = operand.span.to(op.as_span().into());
+        let kind = match op.as_rule() {
+            Rule::factorial => ExprKind::Factorial(Box::new(operand)),
+            Rule::percent => ExprKind::Percent(Box::new(operand)),
+            rule => unreachable!("parse_expr expected postfix operation, found {:?}", rule),
+        };
+        Expr::new(kind, span)
+    }))
+}
+
+fn parse_primary(pair: Pair<Rule>, depth: Depth) -> Result<Expr, CalcError> {
+    let span = Span::from(pair.as_span());
+    let kind = match pair.as_rule() {
+        Rule::integer => pair
+            .as_str()
+            .replace('_', "")
+            .parse::<Int>()
+            .map(|i| ExprKind::Integer(i, Radix::Decimal))
+            .map_err(|_| CalcError::LiteralOutOfRange(pair.as_str().to_owned()))?,
+        Rule::hexadecimal => parse_radix(pair.as_str(), Radix::Hexadecimal)?,
+        Rule::octal => parse_radix(pair.as_str(), Radix::Octal)?,
+        Rule::binary => parse_radix(pair.as_str(), Radix::Binary)?,
+        Rule::decimal => ExprKind::Float(pair.as_str().replace('_', "").parse::<f64>().unwrap()),
+        Rule::identifier => match Constant::from_name(pair.as_str()) {
+            Some(constant) => ExprKind::Constant(constant),
+            None => ExprKind::Variable(pair.as_str().to_owned()),
+        },
+        Rule::call => {
+            let mut pairs = pair.into_inner();
+            let name = pairs.next().unwrap().as_str().to_owned();
+            let depth = depth.nested()?;
+            let mut args: Vec<Expr> = Vec::new();
+            for arg in pairs {
+                match arg.as_rule() {
+                    // The end of a range follows its start, which was parsed as the first argument.
+                    Rule::range_end => {
+                        let start = args.pop().unwrap();
+                        let end = build_expr(arg.into_inner().next().unwrap().into_inner(), depth)?;
+                        let span = start.span.to(end.span);
+                        args.push(Expr::new(ExprKind::Range { start: Box::new(start), end: Box::new(end) }, span));
+                    }
+                    _ => args.push(build_expr(arg.into_inner(), depth)?),
+                }
+            }
+            ExprKind::Call { name, args }
+        }
+        // expression in parentheses, whose span includes the parentheses.
+        Rule::parenthesized => build_expr(pair.into_inner(), depth.nested()?)?.kind,
+        Rule::boolean => ExprKind::Bool(pair.as_str() == "true"),
+        Rule::assignment => parse_assignment(pair, depth)?,
+        Rule::let_in => parse_let(pair, depth)?,
+        // The operand is an operation of only `^`, see the grammar.
+        Rule::unary_minus => ExprKind::UnaryMinus(Box::new(parse_operation(pair.into_inner(), depth.nested()?)?)),
+        Rule::not => ExprKind::Not(Box::new(build_expr(pair.into_inner(), depth.nested()?)?)),
+        rule => unreachable!("parse_expr expected atom, found {:?}", rule)
+    };
+    Ok(Expr::new(kind, span))
+}
+
+/// Parses a literal with a two character radix prefix such as `0x`.
+fn parse_radix(literal: &str, radix: Radix) -> Result<ExprKind, CalcError> {
+    int::parse(&literal[2..], radix.base())
+        .map(|i| ExprKind::Integer(i, radix))
+        .ok_or_else(|| CalcError::LiteralOutOfRange(literal.to_owned()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Op {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    /// `//`, which rounds the quotient down, so `-7 // 2` is `-4` where `-7 / 2` is `-3`.
+    FloorDivide,
+    Modulo,
+    Power,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+/// A prefix or postfix operator, which [`ExprKind`] represents with a variant of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOp {
+    /// Prefix `-`.
+    Minus,
+    /// Prefix `!`.
+    Not,
+    /// Postfix `!`.
+    Factorial,
+    /// Postfix `%`.
+    Percent,
+}
+
+/// Which side operators of the same precedence group to, see [`Op::associativity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `1 - 2 - 3` is `(1 - 2) - 3`.
+    Left,
+    /// `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.
+    Right,
+}
+
+impl Op {
+    const ALL: [Op; 20] = [
+        Op::Add, Op::Subtract, Op::Multiply, Op::Divide, Op::FloorDivide, Op::Modulo, Op::Power,
+        Op::Less, Op::LessEqual, Op::Greater, Op::GreaterEqual, Op::Equal, Op::NotEqual,
+        Op::And, Op::Or, Op::BitAnd, Op::BitOr, Op::BitXor, Op::ShiftLeft, Op::ShiftRight,
+    ];
+
+    /// How tightly the operator binds its operands, from 1 for `||` to 11 for `^`.
+    ///
+    /// The operator with the higher precedence is applied first, so `1 + 2 * 3` is `1 + (2 * 3)`.
+    /// This is the table the parser is built from.
+    pub const fn precedence(&self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Equal | Op::NotEqual => 3,
+            Op::Less | Op::LessEqual | Op::Greater | Op::GreaterEqual => 4,
+            Op::BitOr => 5,
+            Op::BitXor => 6,
+            Op::BitAnd => 7,
+            Op::ShiftLeft | Op::ShiftRight => 8,
+            Op::Add | Op::Subtract => 9,
+            Op::Multiply | Op::Divide | Op::FloorDivide | Op::Modulo => 10,
+            Op::Power => 11,
+        }
+    }
+
+    /// How a chain of operators of the same precedence is grouped. Only `^` is right associative.
+    pub fn associativity(&self) -> Associativity {
+        match self {
+            Op::Power => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+
+    /// The grammar rules of the operator. Juxtaposition with implicit multiplication is a multiplication too.
+    fn rules(&self) -> &'static [Rule] {
+        match self {
+            Op::Add => &[Rule::add],
+            Op::Subtract => &[Rule::subtract],
+            Op::Multiply => &[Rule::multiply, Rule::implicit_multiply],
+            Op::Divide => &[Rule::divide],
+            Op::FloorDivide => &[Rule::floor_divide],
+            Op::Modulo => &[Rule::modulo],
+            Op::Power => &[Rule::power],
+            Op::Less => &[Rule::less],
+            Op::LessEqual => &[Rule::less_equal],
+            Op::Greater => &[Rule::greater],
+            Op::GreaterEqual => &[Rule::greater_equal],
+            Op::Equal => &[Rule::equal],
+            Op::NotEqual => &[Rule::not_equal],
+            Op::And => &[Rule::and],
+            Op::Or => &[Rule::or],
+            Op::BitAnd => &[Rule::bit_and],
+            Op::BitOr => &[Rule::bit_or],
+            Op::BitXor => &[Rule::bit_xor],
+            Op::ShiftLeft => &[Rule::shift_left],
+            Op::ShiftRight => &[Rule::shift_right],
+        }
+    }
+}
+
+/// Any error that can occur while parsing or evaluating an input string.
+///
+/// With the `serde` feature, parse and I/O errors serialize as their messages.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CalcError {
+    // Boxed because pest errors are large compared to everything else in a `Result`.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+    Parse(Box<pest::error::Error<Rule>>),
+    Eval(EvalError),
+    /// A literal in the input does not fit in an [`Int`].
+    LiteralOutOfRange(String),
+    /// An assignment to a name that is reserved for a constant or keyword.
+    ReservedName(String),
+    /// The input could not be read.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+    Io(io::Error),
+    /// The input nests more deeply than the given limit, see [`ParseConfig::max_depth`].
+    TooDeep(usize),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalcError::Parse(e) => write!(f, "parse error\n{}", e),
+            CalcError::Eval(e) => e.fmt(f),
+            CalcError::LiteralOutOfRange(literal) => write!(f, "literal {} is out of range", literal),
+            CalcError::ReservedName(name) => write!(f, "cannot assign to reserved name {}", name),
+            CalcError::Io(e) => write!(f, "cannot read input: {}", e),
+            CalcError::TooDeep(max_depth) => write!(f, "expression is nested more than {} levels deep", max_depth),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// Serializes `value` as its [`Display`](fmt::Display) output, for errors from other crates.
+#[cfg(feature = "serde")]
+fn serialize_display<T: fmt::Display, S: serde::Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+impl CalcError {
+    /// Whether this error from parsing `input` only means that the input ended too early, as for `2 * (3 +`
+    /// or `max(1,`, so that continuing it on the next line could make it valid.
+    ///
+    /// That is the case for a parse error at the end of the input, ignoring trailing whitespace.
+    /// An invalid input such as `1 2)` fails before its end, and a blank input is not incomplete either.
+    pub fn is_incomplete(&self, input: &str) -> bool {
+        match self {
+            CalcError::Parse(e) => match e.location {
+                InputLocation::Pos(pos) => !input.trim().is_empty() && pos >= input.trim_end().len(),
+                InputLocation::Span(_) => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Uses the names from [`rule_name`] in the expectations of `e`, listing each name only once.
+impl From<pest::error::Error<Rule>> for CalcError {
+    fn from(mut e: pest::error::Error<Rule>) -> Self {
+        if let ErrorVariant::ParsingError {
+            positives,
+            negatives,
+        } = &mut e.variant
+        {
+            let mut seen = HashSet::new();
+            positives.retain(|rule| seen.insert(rule_name(rule)));
+            negatives.retain(|rule| seen.insert(rule_name(rule)));
+        }
+        CalcError::Parse(Box::new(e.renamed_rules(rule_name)))
+    }
+}
+
+/// Describes what a rule matches in terms a user knows, rather than the name in the grammar.
+fn rule_name(rule: &Rule) -> String {
+    let name = match rule {
+        Rule::integer | Rule::hexadecimal | Rule::octal | Rule::binary | Rule::decimal => "number",
+        Rule::boolean => "boolean",
+        Rule::identifier => "variable",
+        Rule::call => "function call",
+        Rule::unary_minus => "`-`",
+        Rule::not | Rule::factorial => "`!`",
+        Rule::percent => "`%`",
+        // Only the `in` of a `let` can be expected after an operand.
+        Rule::keyword => "`in`",
+        Rule::parenthesized => "`(`",
+        Rule::range_end => "`..`",
+        Rule::add
+        | Rule::subtract
+        | Rule::multiply
+        | Rule::divide
+        | Rule::floor_divide
+        | Rule::modulo
+        | Rule::power
+        | Rule::less_equal
+        | Rule::greater_equal
+        | Rule::equal
+        | Rule::not_equal
+        | Rule::less
+        | Rule::greater
+        | Rule::and
+        | Rule::or
+        | Rule::bit_and
+        | Rule::bit_or
+        | Rule::bit_xor
+        | Rule::shift_left
+        | Rule::shift_right
+        | Rule::implicit_multiply => "operator",
+        Rule::EOI => "end of input",
+        Rule::postfix => "operand",
+        Rule::expr | Rule::operation | Rule::assignment | Rule::let_in => "expression",
+        rule => return format!("{:?}", rule),
+    };
+    name.to_owned()
+}
+
+impl From<EvalError> for CalcError {
+    fn from(e: EvalError) -> Self {
+        CalcError::Eval(e)
+    }
+}
+
+impl From<io::Error> for CalcError {
+    fn from(e: io::Error) -> Self {
+        CalcError::Io(e)
+    }
+}
+
+/// Parses and evaluates `input` as a single expression.
+pub fn evaluate(input: &str) -> Result<Value, CalcError> {
+    let config = ParseConfig::default();
+    let pairs = config.parse(Rule::equation, input)?;
+    Ok(eval(&config.parse_expr(pairs)?)?)
+}
+
+/// Evaluates `input` like [`evaluate`] and formats the outcome as text.
+///
+/// A result is formatted with its `Display` impl and an error as `Error: ` followed by its message,
+/// matching what the command line tool prints. Nothing on this path reads or writes any I/O stream,
+/// so it can be wrapped for the browser with `wasm-bindgen`.
+pub fn evaluate_to_string(input: &str) -> String {
+    match evaluate(input) {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+/// Parses and evaluates each of `inputs` as a single expression, like [`evaluate`].
+///
+/// The inputs are independent: nothing assigned in one is visible to the next.
+/// The operator precedence table is built once on first use and shared by every call.
+pub fn eval_all(inputs: &[&str]) -> Vec<Result<Value, CalcError>> {
+    inputs.iter().map(|input| evaluate(input)).collect()
+}
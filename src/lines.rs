@@ -0,0 +1,256 @@
+//! Evaluating input line by line, as the command line tool does.
+
+use std::borrow::BorrowMut;
+use std::io::{self, BufRead};
+use std::time::{Duration, Instant};
+
+use crate::{CalcError, Environment, EvalError, Expr, ParseConfig, Rule, Value};
+
+/// Lazily evaluates every line read from `reader`, yielding one result per statement.
+///
+/// The lines share one [`Environment`], so variables assigned on one line can be used on the next.
+/// Every statement of a line is evaluated even if one before it fails, unlike with [`Environment::eval_line`].
+/// A line that fails to parse yields a single error, and lines that are empty or only hold whitespace
+/// are skipped without a result. See [`EvalLines`] for more control over how lines are evaluated.
+pub fn eval_lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Value, CalcError>> {
+    EvalLines::new(Environment::new(), reader).flat_map(|line| match line {
+        Ok(EvaluatedLine {
+            statements: Ok(statements),
+            ..
+        }) => statements
+            .into_iter()
+            // Statements are always evaluated, since this iterator is not `parse_only`.
+            .map(|statement| Ok(statement.result.unwrap()?))
+            .collect(),
+        Ok(EvaluatedLine {
+            statements: Err(e), ..
+        }) => vec![Err(e)],
+        Err(e) => vec![Err(e.into())],
+    })
+}
+
+/// An iterator that reads lines from `R` and evaluates their statements in an [`Environment`],
+/// yielding an [`EvaluatedLine`] for each line that is not blank.
+///
+/// `E` is the environment, or a mutable reference to one that outlives the iterator.
+/// A line whose last statement evaluates to a value also records it for `ans`, see [`Environment::set_ans`],
+/// so the statements of a line all see the result of the line before it.
+pub struct EvalLines<E, R> {
+    env: E,
+    reader: R,
+    config: ParseConfig,
+    continuation: Option<Box<dyn FnMut()>>,
+    whole: bool,
+    steps: bool,
+    evaluate: bool,
+    /// The number of the next line to be read.
+    line: usize,
+}
+
+/// A line read by [`EvalLines`], together with the outcome of its statements.
+#[derive(Debug)]
+pub struct EvaluatedLine {
+    /// The text of the line, with the lines it was continued on joined by newlines.
+    pub text: String,
+    /// The number of the first line of `text` in the input, counting from 1.
+    pub number: usize,
+    /// Every statement of the line, or the error if the line failed to parse.
+    pub statements: Result<Vec<EvaluatedStatement>, CalcError>,
+    /// The time spent parsing and evaluating the line, not counting the time spent reading it.
+    pub elapsed: Duration,
+}
+
+/// A statement of an [`EvaluatedLine`].
+#[derive(Debug)]
+pub struct EvaluatedStatement {
+    /// The statement, whose span is relative to the text of the line.
+    pub expr: Expr,
+    /// The outcome of evaluating the statement, which is `None` with [`EvalLines::parse_only`].
+    pub result: Option<Result<Value, EvalError>>,
+    /// The steps of the evaluation as [`Environment::eval_steps`] lists them, if [`EvalLines::steps`] asked for them
+    /// and evaluation succeeded.
+    pub steps: Vec<String>,
+}
+
+impl<E: BorrowMut<Environment>, R: BufRead> EvalLines<E, R> {
+    /// Evaluates the lines of `reader` in `env`, parsing them with the default [`ParseConfig`].
+    pub fn new(env: E, reader: R) -> Self {
+        EvalLines {
+            env,
+            reader,
+            config: ParseConfig::default(),
+            continuation: None,
+            whole: false,
+            steps: false,
+            evaluate: true,
+            line: 1,
+        }
+    }
+
+    /// Parses lines according to `config`.
+    pub fn parse_config(mut self, config: ParseConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Continues incomplete lines on the lines after them, calling `continuation` before reading each one.
+    ///
+    /// See [`read_entry`]. Lines that are cancelled with a blank line are skipped.
+    pub fn continue_incomplete(mut self, continuation: impl FnMut() + 'static) -> Self {
+        self.continuation = Some(Box::new(continuation));
+        self
+    }
+
+    /// Reads all of the input as a single line, such as an expression given on the command line
+    /// that spans several lines.
+    pub fn whole_input(mut self) -> Self {
+        self.whole = true;
+        self
+    }
+
+    /// Evaluates statements with [`Environment::eval_steps`], recording their steps.
+    pub fn steps(mut self) -> Self {
+        self.steps = true;
+        self
+    }
+
+    /// Only parses lines, without evaluating their statements.
+    pub fn parse_only(mut self) -> Self {
+        self.evaluate = false;
+        self
+    }
+
+    fn next_entry(&mut self) -> io::Result<Option<Entry>> {
+        if self.whole {
+            let mut text = String::new();
+            if self.reader.read_to_string(&mut text)? == 0 {
+                return Ok(None);
+            }
+            let lines = text.lines().count();
+            let text = text.trim_end_matches(['\n', '\r']).to_owned();
+            return Ok(Some(Entry::Text { text, lines }));
+        }
+        if let Some(continuation) = &mut self.continuation {
+            return read_entry(&mut self.reader, &self.config, continuation);
+        }
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        // Strips the line ending like `BufRead::lines`.
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let text = line.strip_suffix('\r').unwrap_or(line).to_owned();
+        Ok(Some(Entry::Text { text, lines: 1 }))
+    }
+
+    fn eval(&mut self, expr: Expr) -> EvaluatedStatement {
+        let env = self.env.borrow_mut();
+        let (result, steps) = match (self.evaluate, self.steps) {
+            (false, _) => (None, Vec::new()),
+            (true, false) => (Some(env.eval(&expr)), Vec::new()),
+            (true, true) => match env.eval_steps(&expr) {
+                Ok((value, steps)) => (Some(Ok(value)), steps),
+                Err(e) => (Some(Err(e)), Vec::new()),
+            },
+        };
+        EvaluatedStatement {
+            expr,
+            result,
+            steps,
+        }
+    }
+}
+
+impl<E: BorrowMut<Environment>, R: BufRead> Iterator for EvalLines<E, R> {
+    type Item = io::Result<EvaluatedLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let number = self.line;
+            let text = match self.next_entry() {
+                Ok(Some(Entry::Text { text, lines })) => {
+                    self.line += lines;
+                    text
+                }
+                Ok(Some(Entry::Cancelled { lines })) => {
+                    self.line += lines;
+                    continue;
+                }
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            // Blank lines are skipped like in a shell, rather than failing to parse with nothing to evaluate.
+            if text.trim().is_empty() {
+                continue;
+            }
+            let start = Instant::now();
+            let statements = self
+                .config
+                .parse(Rule::program, &text)
+                .and_then(|pairs| self.config.parse_program(pairs))
+                .map(|statements| {
+                    statements
+                        .into_iter()
+                        .map(|statement| self.eval(statement))
+                        .collect::<Vec<_>>()
+                });
+            if let Ok([.., last]) = statements.as_deref() {
+                if let Some(Ok(value)) = &last.result {
+                    self.env.borrow_mut().set_ans(value.clone());
+                }
+            }
+            return Some(Ok(EvaluatedLine {
+                text,
+                number,
+                statements,
+                elapsed: start.elapsed(),
+            }));
+        }
+    }
+}
+
+/// Input read by [`read_entry`].
+#[derive(Debug, PartialEq)]
+pub enum Entry {
+    /// One or more lines joined with newlines, which only form an incomplete program if the input ended.
+    Text { text: String, lines: usize },
+    /// Lines that were abandoned with a blank line before they formed a complete program.
+    Cancelled { lines: usize },
+}
+
+/// Reads a line from `reader`, continuing it with the lines after it for as long as it is incomplete.
+///
+/// A line is incomplete if parsing it as a program according to `config` fails at its end,
+/// see [`CalcError::is_incomplete`], so `2 * (3 +` and `4)` on the next line are read together as `2 * (3 +\n4)`.
+/// `continuation` is called before each continuation line is read, e.g. to show a prompt.
+/// A blank continuation line cancels the entry. Returns `None` once the input is exhausted.
+pub fn read_entry(
+    reader: &mut impl BufRead,
+    config: &ParseConfig,
+    mut continuation: impl FnMut(),
+) -> io::Result<Option<Entry>> {
+    let mut text = String::new();
+    let mut lines = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            // An entry the input ends in the middle of is returned as it is, to report its error.
+            return Ok((lines > 0).then_some(Entry::Text { text, lines }));
+        }
+        // Strips the line ending like `BufRead::lines`.
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        lines += 1;
+        if lines > 1 {
+            if line.trim().is_empty() {
+                return Ok(Some(Entry::Cancelled { lines }));
+            }
+            text.push('\n');
+        }
+        text.push_str(line);
+        match config.parse(Rule::program, &text) {
+            Err(e) if e.is_incomplete(&text) => continuation(),
+            _ => return Ok(Some(Entry::Text { text, lines })),
+        }
+    }
+}
@@ -1,82 +1,470 @@
+// Values are only `Copy` without the `bignum` feature, so code shared by both has to clone them.
+#![cfg_attr(not(feature = "bignum"), allow(clippy::clone_on_copy))]
 
-use std::io::{self, BufRead};
-use pest::iterators::{Pair, Pairs};
-use pest::prec_climber::PrecClimber;
-use pest::Parser;
+use std::env;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write as _};
+use std::process::ExitCode;
+use std::time::Duration;
 
-#[derive(pest_derive::Parser)]
-#[grammar = "calculator.pest"]
-pub struct CalculatorParser;
+use pest::error::{Error, LineColLocation};
+use pest_calculator::{
+    format_grouped, format_result, render_tree, CalcError, Environment, EvalConfig, EvalLines,
+    ParseConfig, Radix, Rule, Span, Value,
+};
 
-lazy_static::lazy_static! {
-    static ref PREC_CLIMBER: PrecClimber<Rule> = {
-        use pest::prec_climber::{Assoc::*, Operator};
-        use Rule::*;
+/// When to use colors, as given by `--color`.
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Color {
+    Never,
+    /// Only if stdout is a terminal.
+    #[default]
+    Auto,
+    Always,
+}
 
-        PrecClimber::new(vec![
-            Operator::new(add, Left) | Operator::new(subtract, Left),
-            Operator::new(multiply, Left) | Operator::new(divide, Left) | Operator::new(modulo, Left),
-        ])
-    };
+impl Color {
+    fn parse(when: Option<&str>) -> Result<Color, String> {
+        match when {
+            Some("never") => Ok(Color::Never),
+            Some("auto") => Ok(Color::Auto),
+            Some("always") => Ok(Color::Always),
+            Some(when) => Err(format!("unknown color mode {}", when)),
+            None => Err("--color needs a value".to_owned()),
+        }
+    }
+
+    fn enabled(self) -> bool {
+        match self {
+            Color::Never => false,
+            Color::Auto => io::stdout().is_terminal(),
+            Color::Always => true,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub enum Expr {
-    Integer(i32),
-    UnaryMinus(Box<Expr>),
-    BinOp {
-        lhs: Box<Expr>,
-        op: Op,
-        rhs: Box<Expr>,
-    },
+/// Command line options, see `usage`.
+#[derive(Default)]
+struct Options {
+    /// Print one JSON object per statement instead of the human readable output.
+    json: bool,
+    /// Show the parsed tree with branch characters instead of its `Debug` representation.
+    tree: bool,
+    /// The base integer results are printed in, instead of the one suggested by the literals of each statement.
+    /// JSON output always uses decimal numbers.
+    radix: Option<Radix>,
+    /// The separator to group the digits of decimal results with, if any. JSON output is never grouped.
+    group: Option<char>,
+    /// Multiply juxtaposed operands, see `ParseConfig::implicit_multiplication`.
+    implicit_multiplication: bool,
+    /// Make `/` divide in floating point, see `EvalConfig::float_division`.
+    float_division: bool,
+    /// Print every reduction step instead of the parsed tree. Ignored for JSON output.
+    steps: bool,
+    /// Print a summary of all lines once the input is exhausted.
+    stats: bool,
+    /// Only parse every line, reporting syntax errors, and fail if there were any.
+    check: bool,
+    /// When to echo each line with syntax highlighting before its output. JSON output is never highlighted.
+    color: Color,
+    /// Expressions given with `--expr`, which are evaluated instead of reading stdin.
+    exprs: Vec<String>,
+    paths: Vec<String>,
 }
 
-pub fn parse_expr(pairs: Pairs<Rule>) -> Expr {
-    PREC_CLIMBER.climb(
-        pairs,
-        |pair: Pair<Rule>| match pair.as_rule() {
-            Rule::integer => Expr::Integer(pair.as_str().parse::<i32>().unwrap()),
-            // expression in parentheses.
-            Rule::expr => parse_expr(pair.into_inner()),
-            Rule::unary_minus => Expr::UnaryMinus(Box::new(parse_expr(pair.into_inner()))),
-            rule => unreachable!("Expr::parse expected atom, found {:?}", rule)
-        },
-        |lhs: Expr, op: Pair<Rule>, rhs: Expr| {
-            let op = match op.as_rule() {
-                Rule::add => Op::Add,
-                Rule::subtract => Op::Subtract,
-                Rule::multiply => Op::Multiply,
-                Rule::divide => Op::Divide,
-                Rule::modulo => Op::Modulo,
-                rule => unreachable!("Expr::parse expected infix operation, found {:?}", rule),
-            };
-            Expr::BinOp {
-                lhs: Box::new(lhs),
-                op,
-                rhs: Box::new(rhs),
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "--tree" => options.tree = true,
+                "--steps" => options.steps = true,
+                "--stats" => options.stats = true,
+                "--check" => options.check = true,
+                "--group" => options.group = options.group.or(Some(',')),
+                "--group-separator" => {
+                    options.group = Some(match args.next().as_deref() {
+                        Some(",") => ',',
+                        Some("_") => '_',
+                        Some(separator) => {
+                            return Err(format!("unknown group separator {}", separator))
+                        }
+                        None => return Err("--group-separator needs a value".to_owned()),
+                    })
+                }
+                "--implicit-multiplication" => options.implicit_multiplication = true,
+                "--float-division" => options.float_division = true,
+                "--expr" => match args.next() {
+                    Some(expr) => options.exprs.push(expr),
+                    None => return Err("--expr needs a value".to_owned()),
+                },
+                "--color" => options.color = Color::parse(args.next().as_deref())?,
+                flag if flag.starts_with("--color=") => {
+                    options.color = Color::parse(flag.strip_prefix("--color="))?
+                }
+                "--radix" => {
+                    options.radix = Some(match args.next().as_deref() {
+                        Some("dec") => Radix::Decimal,
+                        Some("hex") => Radix::Hexadecimal,
+                        Some("oct") => Radix::Octal,
+                        Some("bin") => Radix::Binary,
+                        Some(radix) => return Err(format!("unknown radix {}", radix)),
+                        None => return Err("--radix needs a value".to_owned()),
+                    })
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+                _ => options.paths.push(arg),
             }
-        })
+        }
+        Ok(options)
+    }
+}
+
+fn usage() -> &'static str {
+    "usage: pest-calculator [--json] [--tree] [--steps] [--stats] [--check] [--group] [--group-separator ,|_] [--radix dec|hex|oct|bin] [--implicit-multiplication] [--float-division] [--color never|auto|always] [--expr EXPRESSION]... [FILE]..."
+}
+
+/// Quotes and escapes `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
 }
 
-#[derive(Debug)]
-pub enum Op {
-    Add,
-    Subtract,
-    Multiply,
-    Divide,
-    Modulo
+fn json_value(value: Value) -> String {
+    match value {
+        Value::Int(i) => i.to_string(),
+        // JSON has no representation for infinities and NaN.
+        Value::Float(f) if !f.is_finite() => "null".to_owned(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::Bool(b) => b.to_string(),
+    }
 }
 
-fn main() -> io::Result<()> {
-    for line in io::stdin().lock().lines() {
-        match CalculatorParser::parse(Rule::equation, &line?) {
-            Ok(pairs) =>{
-                println!("Parsed: {:#?}", parse_expr(pairs));
+/// Prints the outcome of evaluating `input`, in the format selected by `options`.
+///
+/// `input` is the source text of the statement as it was given, so JSON consumers can match results to their input.
+/// Integer results are printed in `preferred` radix, unless `options` select one.
+/// If `bare`, a result is printed on its own rather than after `Result: `, unless `options` select JSON.
+/// Returns whether a result was printed, which it is not for errors or results that cannot be formatted.
+fn report(
+    options: &Options,
+    input: &str,
+    result: Result<Value, String>,
+    preferred: Radix,
+    bare: bool,
+) -> bool {
+    match (options.json, result) {
+        (true, Ok(value)) => {
+            println!(
+                "{{\"input\": {}, \"result\": {}}}",
+                json_string(input),
+                json_value(value)
+            );
+            true
+        }
+        (true, Err(e)) => {
+            println!(
+                "{{\"input\": {}, \"error\": {}}}",
+                json_string(input),
+                json_string(&e)
+            );
+            false
+        }
+        (false, Ok(value)) => {
+            let radix = match (options.radix, &value) {
+                (Some(radix), _) => radix,
+                (None, Value::Int(_)) => preferred,
+                (None, _) => Radix::Decimal,
+            };
+            let formatted = match options.group {
+                Some(separator) if radix == Radix::Decimal => Ok(format_grouped(&value, separator)),
+                _ => format_result(&value, radix),
+            };
+            match formatted {
+                Ok(formatted) if bare => {
+                    println!("{}", formatted);
+                    true
+                }
+                Ok(formatted) => {
+                    println!("Result: {}", formatted);
+                    true
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    false
+                }
             }
+        }
+        (false, Err(e)) => {
+            eprintln!("Error: {}", e);
+            false
+        }
+    }
+}
+
+/// Shows the line of `text` that `span` starts on, with carets under the part of it covered by `span`.
+fn underline(text: &str, span: Span) -> String {
+    let line_start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[span.start..]
+        .find('\n')
+        .map_or(text.len(), |i| span.start + i);
+    let column = text[line_start..span.start].chars().count();
+    let width = text[span.start..span.end.min(line_end)]
+        .chars()
+        .count()
+        .max(1);
+    format!(
+        "  {}\n  {}{}",
+        &text[line_start..line_end],
+        " ".repeat(column),
+        "^".repeat(width)
+    )
+}
+
+/// Moves the error from parsing an entry to its position in the whole input, where the entry starts on `first_line`.
+fn locate(mut e: Error<Rule>, path: Option<&str>, first_line: usize) -> Error<Rule> {
+    let line = |line| first_line + line - 1;
+    e.line_col = match e.line_col {
+        LineColLocation::Pos((start, col)) => LineColLocation::Pos((line(start), col)),
+        LineColLocation::Span((start, start_col), (end, end_col)) => {
+            LineColLocation::Span((line(start), start_col), (line(end), end_col))
+        }
+    };
+    match path {
+        Some(path) => e.with_path(path),
+        None => e,
+    }
+}
+
+/// Counts of the lines evaluated by `run`, and how long they took.
+#[derive(Default)]
+struct RunStats {
+    lines: usize,
+    /// Lines that parsed, even if evaluating them failed.
+    parsed: usize,
+    /// Lines that failed to parse or had a statement that failed to evaluate.
+    failed: usize,
+    /// Time spent parsing and evaluating, without printing.
+    elapsed: Duration,
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let average = match u32::try_from(self.lines) {
+            Ok(lines) if lines > 0 => self.elapsed / lines,
+            _ => Duration::ZERO,
+        };
+        write!(
+            f,
+            "{} lines, {} parsed, {} failed, {:?} total, {:?} per line",
+            self.lines, self.parsed, self.failed, self.elapsed, average
+        )
+    }
+}
+
+/// Evaluates every line read from `reader`, printing the results as it goes and counting them in `stats`.
+///
+/// Blank lines are skipped. `path` names the file that is read, if it is not stdin. If `interactive`, an incomplete line such as
+/// `2 * (3 +` is continued on the next line instead of failing, see `read_entry`.
+/// If `results_only`, all of `reader` is evaluated as a single line, and only the bare results are printed,
+/// without the parsed tree unless `--tree` asks for it.
+fn run(
+    reader: impl BufRead,
+    path: Option<&str>,
+    interactive: bool,
+    results_only: bool,
+    env: &mut Environment,
+    options: &Options,
+    stats: &mut RunStats,
+) -> io::Result<()> {
+    let config = ParseConfig {
+        implicit_multiplication: options.implicit_multiplication,
+        ..ParseConfig::default()
+    };
+    let highlight = options.color.enabled() && !options.json;
+    let steps = options.steps && !options.json;
+    let mut lines = EvalLines::new(env, reader).parse_config(config);
+    if interactive {
+        lines = lines.continue_incomplete(|| {
+            print!("... ");
+            // The prompt is only a convenience, so failing to show it does not stop reading.
+            let _ = io::stdout().flush();
+        });
+    }
+    if results_only {
+        lines = lines.whole_input();
+    }
+    if options.check {
+        lines = lines.parse_only();
+    }
+    if steps {
+        lines = lines.steps();
+    }
+    for line in lines {
+        let line = line?;
+        if highlight {
+            println!("Input: {}", config.highlight(&line.text));
+        }
+        stats.lines += 1;
+        stats.elapsed += line.elapsed;
+        let statements = match line.statements {
+            Ok(statements) => statements,
             Err(e) => {
-                eprintln!("Parse failed: {:?}", e);
+                stats.failed += 1;
+                let e = match e {
+                    CalcError::Parse(e) => {
+                        CalcError::Parse(Box::new(locate(*e, path, line.number)))
+                    }
+                    e => e,
+                };
+                report(
+                    options,
+                    &line.text,
+                    Err(e.to_string()),
+                    Radix::Decimal,
+                    results_only,
+                );
+                continue;
             }
+        };
+        stats.parsed += 1;
+        let mut failed = false;
+        for statement in statements {
+            let Some(result) = statement.result else {
+                continue;
+            };
+            let expr = statement.expr;
+            if options.tree && !options.json && !steps {
+                print!("{}", render_tree(&expr));
+            } else if !options.json && !steps && !results_only {
+                println!("Parsed: {:#?}", expr);
+            }
+            for step in statement.steps {
+                println!("Step: {}", step);
+            }
+            let reported = match result {
+                Err(e) if !options.json => {
+                    eprintln!("Error: {}", e);
+                    eprintln!("{}", underline(&line.text, e.span));
+                    false
+                }
+                result => report(
+                    options,
+                    &line.text[expr.span.start..expr.span.end],
+                    result.map_err(|e| e.to_string()),
+                    expr.preferred_radix(),
+                    results_only,
+                ),
+            };
+            failed |= !reported;
+        }
+        if failed {
+            stats.failed += 1;
         }
     }
     Ok(())
 }
+
+/// Evaluates the expressions given with `--expr` and then the files given as arguments in order,
+/// or stdin if there are neither. Variables defined in one of them remain visible in the ones after it.
+///
+/// The exit status fails if an input cannot be read, if an expression given with `--expr` is empty or fails,
+/// or with `--check` if any line fails to parse. Expressions given with `--expr` only print their results.
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}\n{}", e, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut env = Environment::with_config(EvalConfig {
+        float_division: options.float_division,
+        ..EvalConfig::default()
+    });
+
+    let mut stats = RunStats::default();
+    let mut status = ExitCode::SUCCESS;
+    for expr in &options.exprs {
+        // A blank line of input is skipped, but an empty expression argument is a mistake.
+        if expr.trim().is_empty() {
+            eprintln!("Error: --expr needs a non-empty expression");
+            status = ExitCode::FAILURE;
+            continue;
+        }
+        let failed = stats.failed;
+        // Reading from memory cannot fail.
+        run(
+            expr.as_bytes(),
+            None,
+            false,
+            true,
+            &mut env,
+            &options,
+            &mut stats,
+        )
+        .unwrap();
+        if stats.failed > failed {
+            status = ExitCode::FAILURE;
+        }
+    }
+    if options.paths.is_empty() && options.exprs.is_empty() {
+        let stdin = io::stdin();
+        let interactive = stdin.is_terminal();
+        // `ans` is only a convenience for typing at the prompt, in input that is read again it is a variable.
+        if interactive {
+            env.track_ans();
+        }
+        if let Err(e) = run(
+            stdin.lock(),
+            None,
+            interactive,
+            false,
+            &mut env,
+            &options,
+            &mut stats,
+        ) {
+            eprintln!("Error: cannot read stdin: {}", e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    for path in &options.paths {
+        let result = File::open(path).and_then(|file| {
+            run(
+                BufReader::new(file),
+                Some(path),
+                false,
+                false,
+                &mut env,
+                &options,
+                &mut stats,
+            )
+        });
+        if let Err(e) = result {
+            eprintln!("Error: cannot read {}: {}", path, e);
+            status = ExitCode::FAILURE;
+        }
+    }
+    if options.stats {
+        eprintln!("Stats: {}", stats);
+    }
+    if options.check && stats.failed > 0 {
+        status = ExitCode::FAILURE;
+    }
+    status
+}
@@ -0,0 +1,381 @@
+//! The arithmetic of a single number type, and evaluation of purely numeric expressions in it.
+
+use std::fmt;
+
+use crate::int;
+use crate::{
+    Environment, EvalConfig, EvalError, EvalErrorKind, Expr, ExprKind, Int, ModuloMode, Op, Value,
+};
+
+/// A number type that expressions can be evaluated in with [`eval_as`].
+///
+/// Every operation reports its own errors, so an implementation decides what overflows and what
+/// cannot be represented. [`Environment::eval`] uses the implementations for [`Int`] and `f64`
+/// for integer and floating point arithmetic, where [`Int`] is `i64` unless the `bignum` feature
+/// makes it a `BigInt`.
+pub trait Number: Clone + fmt::Debug + fmt::Display {
+    /// The name of the type in error messages, like the names of [`Value::type_name`].
+    const NAME: &'static str;
+
+    fn from_int(i: Int) -> Result<Self, EvalErrorKind>;
+    fn from_float(x: f64) -> Result<Self, EvalErrorKind>;
+    fn checked_neg(self) -> Result<Self, EvalErrorKind>;
+    fn checked_add(self, rhs: Self) -> Result<Self, EvalErrorKind>;
+    fn checked_sub(self, rhs: Self) -> Result<Self, EvalErrorKind>;
+    fn checked_mul(self, rhs: Self) -> Result<Self, EvalErrorKind>;
+    /// Divides, honouring [`EvalConfig::strict_integer_division`] where it applies.
+    fn checked_div(self, rhs: Self, config: &EvalConfig) -> Result<Self, EvalErrorKind>;
+    /// Divides, rounding the quotient down towards negative infinity.
+    fn checked_floor_div(self, rhs: Self) -> Result<Self, EvalErrorKind>;
+    /// The remainder of a division, rounded according to `mode`.
+    fn checked_rem(self, rhs: Self, mode: ModuloMode) -> Result<Self, EvalErrorKind>;
+    fn checked_pow(self, exponent: Self) -> Result<Self, EvalErrorKind>;
+}
+
+impl Number for i64 {
+    const NAME: &'static str = "int";
+
+    /// Fails if `i` does not fit, which only happens with the `bignum` feature.
+    fn from_int(i: Int) -> Result<Self, EvalErrorKind> {
+        int::to_i64(&i).ok_or(EvalErrorKind::Overflow("conversion"))
+    }
+
+    /// Only accepts floats without a fractional part that are in range, so `2.0` is `2` but `2.5` fails.
+    fn from_float(x: f64) -> Result<Self, EvalErrorKind> {
+        // `i64::MAX as f64` rounds up to 2^63, which is already out of range.
+        if x.fract() == 0.0 && x >= i64::MIN as f64 && x < i64::MAX as f64 {
+            Ok(x as i64)
+        } else {
+            Err(unsupported("a fractional number", Self::NAME))
+        }
+    }
+
+    fn checked_neg(self) -> Result<Self, EvalErrorKind> {
+        i64::checked_neg(self).ok_or(EvalErrorKind::Overflow("negation"))
+    }
+
+    fn checked_add(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        i64::checked_add(self, rhs).ok_or(EvalErrorKind::Overflow("addition"))
+    }
+
+    fn checked_sub(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        i64::checked_sub(self, rhs).ok_or(EvalErrorKind::Overflow("subtraction"))
+    }
+
+    fn checked_mul(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        i64::checked_mul(self, rhs).ok_or(EvalErrorKind::Overflow("multiplication"))
+    }
+
+    fn checked_div(self, rhs: Self, config: &EvalConfig) -> Result<Self, EvalErrorKind> {
+        if rhs == 0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        // `checked_rem` only fails for `i64::MIN / -1`, which is left to overflow below.
+        if config.strict_integer_division && i64::checked_rem(self, rhs).unwrap_or(0) != 0 {
+            return Err(EvalErrorKind::InexactDivision);
+        }
+        i64::checked_div(self, rhs).ok_or(EvalErrorKind::Overflow("division"))
+    }
+
+    fn checked_floor_div(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        if rhs == 0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        let quotient =
+            i64::checked_div(self, rhs).ok_or(EvalErrorKind::Overflow("floor division"))?;
+        // Division rounds towards zero, which is one too high when the exact quotient is negative and not whole.
+        if self % rhs != 0 && (self < 0) != (rhs < 0) {
+            Ok(quotient - 1)
+        } else {
+            Ok(quotient)
+        }
+    }
+
+    fn checked_rem(self, rhs: Self, mode: ModuloMode) -> Result<Self, EvalErrorKind> {
+        if rhs == 0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        match mode {
+            ModuloMode::Truncated => i64::checked_rem(self, rhs),
+            ModuloMode::Euclidean => i64::checked_rem_euclid(self, rhs),
+        }
+        .ok_or(EvalErrorKind::Overflow("modulo"))
+    }
+
+    /// Fails for negative exponents, which have no integer result.
+    fn checked_pow(self, exponent: Self) -> Result<Self, EvalErrorKind> {
+        if exponent < 0 {
+            return Err(unsupported("a negative exponent", Self::NAME));
+        }
+        u32::try_from(exponent)
+            .ok()
+            .and_then(|exponent| i64::checked_pow(self, exponent))
+            .ok_or(EvalErrorKind::Overflow("exponentiation"))
+    }
+}
+
+/// Arbitrary-precision integers, which only fail to divide by zero or to raise to a negative power.
+#[cfg(feature = "bignum")]
+impl Number for num_bigint::BigInt {
+    const NAME: &'static str = "bigint";
+
+    fn from_int(i: Int) -> Result<Self, EvalErrorKind> {
+        Ok(i)
+    }
+
+    /// Only accepts finite floats without a fractional part, so `2.0` is `2` but `2.5` fails.
+    fn from_float(x: f64) -> Result<Self, EvalErrorKind> {
+        match x.fract() == 0.0 {
+            true => num_traits::FromPrimitive::from_f64(x),
+            false => None,
+        }
+        .ok_or(unsupported("a fractional number", Self::NAME))
+    }
+
+    fn checked_neg(self) -> Result<Self, EvalErrorKind> {
+        Ok(-self)
+    }
+
+    fn checked_add(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self + rhs)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self - rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self * rhs)
+    }
+
+    fn checked_div(self, rhs: Self, config: &EvalConfig) -> Result<Self, EvalErrorKind> {
+        use num_traits::Zero;
+
+        if rhs.is_zero() {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        if config.strict_integer_division && !(&self % &rhs).is_zero() {
+            return Err(EvalErrorKind::InexactDivision);
+        }
+        Ok(self / rhs)
+    }
+
+    fn checked_floor_div(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        use num_integer::Integer;
+        use num_traits::Zero;
+
+        if rhs.is_zero() {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        Ok(self.div_floor(&rhs))
+    }
+
+    fn checked_rem(self, rhs: Self, mode: ModuloMode) -> Result<Self, EvalErrorKind> {
+        use num_traits::{Euclid, Zero};
+
+        if rhs.is_zero() {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        Ok(match mode {
+            ModuloMode::Truncated => self % rhs,
+            ModuloMode::Euclidean => self.rem_euclid(&rhs),
+        })
+    }
+
+    /// Fails for negative exponents, which have no integer result, and for exponents that do not fit in a `u32`,
+    /// whose results would not fit in memory.
+    fn checked_pow(self, exponent: Self) -> Result<Self, EvalErrorKind> {
+        if int::is_negative(&exponent) {
+            return Err(unsupported("a negative exponent", Self::NAME));
+        }
+        u32::try_from(&exponent)
+            .map(|exponent| self.pow(exponent))
+            .map_err(|_| EvalErrorKind::Overflow("exponentiation"))
+    }
+}
+
+impl Number for f64 {
+    const NAME: &'static str = "float";
+
+    fn from_int(i: Int) -> Result<Self, EvalErrorKind> {
+        Ok(int::to_f64(&i))
+    }
+
+    fn from_float(x: f64) -> Result<Self, EvalErrorKind> {
+        Ok(x)
+    }
+
+    fn checked_neg(self) -> Result<Self, EvalErrorKind> {
+        Ok(-self)
+    }
+
+    fn checked_add(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self + rhs)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self - rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self * rhs)
+    }
+
+    fn checked_div(self, rhs: Self, _: &EvalConfig) -> Result<Self, EvalErrorKind> {
+        if rhs == 0.0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        Ok(self / rhs)
+    }
+
+    fn checked_floor_div(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        if rhs == 0.0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        Ok((self / rhs).floor())
+    }
+
+    fn checked_rem(self, rhs: Self, mode: ModuloMode) -> Result<Self, EvalErrorKind> {
+        if rhs == 0.0 {
+            return Err(EvalErrorKind::DivisionByZero);
+        }
+        Ok(match mode {
+            ModuloMode::Truncated => self % rhs,
+            ModuloMode::Euclidean => self.rem_euclid(rhs),
+        })
+    }
+
+    fn checked_pow(self, exponent: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self.powf(exponent))
+    }
+}
+
+/// Applies one of `+`, `-`, `*`, `/`, `//`, `%` and `^` to two numbers of the same type.
+pub(crate) fn arithmetic<N: Number>(
+    lhs: N,
+    op: &Op,
+    rhs: N,
+    config: &EvalConfig,
+) -> Result<N, EvalErrorKind> {
+    match op {
+        Op::Add => lhs.checked_add(rhs),
+        Op::Subtract => lhs.checked_sub(rhs),
+        Op::Multiply => lhs.checked_mul(rhs),
+        Op::Divide => lhs.checked_div(rhs, config),
+        Op::FloorDivide => lhs.checked_floor_div(rhs),
+        Op::Modulo => lhs.checked_rem(rhs, config.modulo),
+        Op::Power => lhs.checked_pow(rhs),
+        _ => unreachable!("{:?} is not an arithmetic operator", op),
+    }
+}
+
+fn unsupported(construct: &'static str, number: &'static str) -> EvalErrorKind {
+    EvalErrorKind::Unsupported { construct, number }
+}
+
+/// Evaluates an expression that does not refer to any variables entirely in the number type `N`.
+///
+/// See [`Environment::eval_as`].
+pub fn eval_as<N: Number>(expr: &Expr) -> Result<N, EvalError> {
+    Environment::new().eval_as(expr)
+}
+
+impl Environment {
+    /// Evaluates a purely numeric expression with every literal, variable and intermediate result in `N`,
+    /// so `eval_as::<Int>` computes `7 / 2` as `3` while `eval_as::<f64>` computes it as `3.5`.
+    ///
+    /// Literals, constants and variables are converted with [`Number::from_int`] and [`Number::from_float`],
+    /// and the arithmetic operators, prefix `-` and `let` work as in [`Environment::eval`].
+    /// Everything else, like bools, comparisons, function calls and assignments, fails with
+    /// [`EvalErrorKind::Unsupported`], since a [`Number`] cannot represent it.
+    /// Every node counts towards [`EvalConfig::max_operations`] as it does for [`Environment::eval`].
+    pub fn eval_as<N: Number>(&self, expr: &Expr) -> Result<N, EvalError> {
+        Numeric {
+            env: self,
+            scopes: Vec::new(),
+            operations: 0,
+        }
+        .eval(expr)
+    }
+}
+
+struct Numeric<'e, N> {
+    env: &'e Environment,
+    /// The bindings of the `let` expressions being evaluated, innermost last.
+    scopes: Vec<(&'e str, N)>,
+    /// The nodes evaluated so far.
+    operations: u64,
+}
+
+impl<'e, N: Number> Numeric<'e, N> {
+    fn eval(&mut self, expr: &'e Expr) -> Result<N, EvalError> {
+        let at = |kind| EvalError::new(kind, expr.span);
+        let config = self.env.config();
+        self.operations += 1;
+        if self.operations > config.max_operations {
+            return Err(at(EvalErrorKind::OperationLimitExceeded {
+                max: config.max_operations,
+            }));
+        }
+        config.operators.check(&expr.kind).map_err(at)?;
+        match &expr.kind {
+            ExprKind::Integer(i, _) => N::from_int(i.clone()).map_err(at),
+            ExprKind::Float(x) => N::from_float(*x).map_err(at),
+            ExprKind::Constant(constant) => N::from_float(constant.value()).map_err(at),
+            ExprKind::Variable(name) => {
+                let bound = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find(|(bound, _)| bound == name)
+                    .map(|(_, value)| value.clone());
+                match (bound, self.env.get(name)) {
+                    (Some(value), _) => Ok(value),
+                    (None, Some(Value::Int(i))) => N::from_int(i).map_err(at),
+                    (None, Some(Value::Float(x))) => N::from_float(x).map_err(at),
+                    (None, Some(Value::Bool(_))) => Err(at(unsupported("a bool", N::NAME))),
+                    (None, None) => Err(at(EvalErrorKind::UndefinedVariable(name.clone()))),
+                }
+            }
+            ExprKind::UnaryMinus(operand) => self.eval(operand)?.checked_neg().map_err(at),
+            ExprKind::BinOp {
+                lhs,
+                op:
+                    op @ (Op::Add
+                    | Op::Subtract
+                    | Op::Multiply
+                    | Op::Divide
+                    | Op::FloorDivide
+                    | Op::Modulo
+                    | Op::Power),
+                rhs,
+            } => {
+                let lhs = self.eval(lhs)?;
+                let rhs = self.eval(rhs)?;
+                arithmetic(lhs, op, rhs, config).map_err(at)
+            }
+            ExprKind::Let { name, value, body } => {
+                let value = self.eval(value)?;
+                self.scopes.push((name, value));
+                let result = self.eval(body);
+                self.scopes.pop();
+                result
+            }
+            kind => {
+                let construct = match kind {
+                    ExprKind::Bool(_) => "a bool",
+                    ExprKind::Not(_) => "`!`",
+                    ExprKind::Factorial(_) => "a factorial",
+                    ExprKind::Percent(_) => "a percentage",
+                    ExprKind::BinOp { .. } => "a comparison, logical or bitwise operator",
+                    ExprKind::Call { .. } => "a function call",
+                    ExprKind::Conditional { .. } => "a conditional",
+                    ExprKind::Assign { .. } => "an assignment",
+                    ExprKind::Range { .. } => "a range",
+                    _ => unreachable!("{:?} is handled above", kind),
+                };
+                Err(at(unsupported(construct, N::NAME)))
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Conversion of expressions to Reverse Polish Notation, and evaluation of the result with a stack.
+
+use crate::{Environment, EvalErrorKind, Expr, ExprKind, Op, Span, Value};
+
+/// A token of an expression in Reverse Polish Notation, where every operator follows its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// A literal, which may also be a bool or the value of a constant.
+    Num(Value),
+    Variable(String),
+    /// A binary operator, applied to the two values below it.
+    Op(Op),
+    /// Prefix `-`.
+    UnaryNeg,
+    /// Prefix `!`.
+    Not,
+    /// Postfix `!`.
+    Factorial,
+    /// Postfix `%`.
+    Percent,
+    /// A call of the named function with the given number of arguments below it.
+    Call {
+        name: String,
+        args: usize,
+    },
+}
+
+/// Lists the tokens of `expr` in Reverse Polish Notation, so `2 + 3 * 4` becomes `2 3 4 * +`.
+///
+/// The tree already orders the operators, so the tokens are simply its nodes in post-order.
+///
+/// # Panics
+///
+/// Panics if `expr` contains a conditional, an assignment, `let` or a range, which have no tokens.
+/// Use [`try_to_rpn`] for expressions that may contain them.
+pub fn to_rpn(expr: &Expr) -> Vec<Token> {
+    try_to_rpn(expr).unwrap_or_else(|| panic!("{} has no tokens in Reverse Polish Notation", expr))
+}
+
+/// Lists the tokens of `expr` like [`to_rpn`], or returns `None` if it contains a conditional,
+/// an assignment, `let` or a range.
+pub fn try_to_rpn(expr: &Expr) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    push_tokens(expr, &mut tokens)?;
+    Some(tokens)
+}
+
+fn push_tokens(expr: &Expr, tokens: &mut Vec<Token>) -> Option<()> {
+    let token = match &expr.kind {
+        ExprKind::Integer(i, _) => Token::Num(Value::Int(i.clone())),
+        ExprKind::Float(x) => Token::Num(Value::Float(*x)),
+        ExprKind::Bool(b) => Token::Num(Value::Bool(*b)),
+        ExprKind::Constant(constant) => Token::Num(Value::Float(constant.value())),
+        ExprKind::Variable(name) => Token::Variable(name.clone()),
+        ExprKind::UnaryMinus(operand) => {
+            push_tokens(operand, tokens)?;
+            Token::UnaryNeg
+        }
+        ExprKind::Not(operand) => {
+            push_tokens(operand, tokens)?;
+            Token::Not
+        }
+        ExprKind::Factorial(operand) => {
+            push_tokens(operand, tokens)?;
+            Token::Factorial
+        }
+        ExprKind::Percent(operand) => {
+            push_tokens(operand, tokens)?;
+            Token::Percent
+        }
+        ExprKind::BinOp { lhs, op, rhs } => {
+            push_tokens(lhs, tokens)?;
+            push_tokens(rhs, tokens)?;
+            Token::Op(op.clone())
+        }
+        ExprKind::Call { name, args } => {
+            for arg in args {
+                push_tokens(arg, tokens)?;
+            }
+            Token::Call {
+                name: name.clone(),
+                args: args.len(),
+            }
+        }
+        ExprKind::Conditional { .. }
+        | ExprKind::Assign { .. }
+        | ExprKind::Let { .. }
+        | ExprKind::Range { .. } => return None,
+    };
+    tokens.push(token);
+    Some(())
+}
+
+/// Evaluates tokens in Reverse Polish Notation that do not refer to any variables.
+///
+/// See [`Environment::eval_rpn`].
+pub fn eval_rpn(tokens: &[Token]) -> Result<Value, EvalErrorKind> {
+    Environment::new().eval_rpn(tokens)
+}
+
+impl Environment {
+    /// Evaluates tokens in Reverse Polish Notation with a stack, giving the same results as [`Environment::eval`]
+    /// on the tree they were made from, except that `&&` and `||` always evaluate both operands.
+    ///
+    /// Tokens carry no spans, so errors are only reported by their kind. Tokens that do not leave exactly one value
+    /// on the stack, such as `1 2` or `1 +`, fail with [`EvalErrorKind::MalformedRpn`].
+    pub fn eval_rpn(&mut self, tokens: &[Token]) -> Result<Value, EvalErrorKind> {
+        self.reset_operations();
+        let mut stack = Vec::new();
+        for token in tokens {
+            let operands = match token {
+                Token::Num(_) | Token::Variable(_) => 0,
+                Token::Op(_) => 2,
+                Token::Call { args, .. } => *args,
+                Token::UnaryNeg | Token::Not | Token::Factorial | Token::Percent => 1,
+            };
+            let at = stack
+                .len()
+                .checked_sub(operands)
+                .ok_or(EvalErrorKind::MalformedRpn)?;
+            let mut operands = stack.split_off(at).into_iter().map(literal);
+            let mut operand = || Box::new(operands.next().unwrap());
+            // Every token is evaluated by the environment as a node whose operands are literals.
+            let kind = match token {
+                Token::Num(value) => literal(value.clone()).kind,
+                Token::Variable(name) => ExprKind::Variable(name.clone()),
+                Token::Op(op) => ExprKind::BinOp {
+                    lhs: operand(),
+                    op: op.clone(),
+                    rhs: operand(),
+                },
+                Token::UnaryNeg => ExprKind::UnaryMinus(operand()),
+                Token::Not => ExprKind::Not(operand()),
+                Token::Factorial => ExprKind::Factorial(operand()),
+                Token::Percent => ExprKind::Percent(operand()),
+                Token::Call { name, args } => ExprKind::Call {
+                    name: name.clone(),
+                    args: (0..*args).map(|_| *operand()).collect(),
+                },
+            };
+            let value = self
+                .eval_node(&Expr::new(kind, Span::default()))
+                .map_err(|e| e.kind)?;
+            stack.push(value);
+        }
+        match stack.as_slice() {
+            [value] => Ok(value.clone()),
+            _ => Err(EvalErrorKind::MalformedRpn),
+        }
+    }
+}
+
+fn literal(value: Value) -> Expr {
+    let kind = match value {
+        Value::Int(i) => ExprKind::Integer(i, Default::default()),
+        Value::Float(x) => ExprKind::Float(x),
+        Value::Bool(b) => ExprKind::Bool(b),
+    };
+    Expr::new(kind, Span::default())
+}
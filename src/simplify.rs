@@ -0,0 +1,99 @@
+use crate::{Expr, ExprKind, Number, Span};
+
+/// Collapses nested negations, so `--5` becomes `5`, `---x` becomes `-x` and `-(2 + 3)` stays as it is.
+///
+/// A negated number literal becomes a negative literal, and an odd number of negations of anything else
+/// becomes a single one. An even number of negations of anything other than a literal is kept as two,
+/// since `--x` fails rather than giving `x` if `x` is a bool or [`Int::MIN`](crate::Int).
+/// Evaluating the result with the default [`EvalConfig`](crate::EvalConfig) gives the same value or error
+/// as evaluating `expr`. A collapsed node keeps the span of the outermost negation.
+pub fn simplify(expr: Expr) -> Expr {
+    let span = expr.span;
+    let kind = match expr.kind {
+        ExprKind::UnaryMinus(operand) => return simplify_negations(span, *operand),
+        ExprKind::Not(operand) => ExprKind::Not(simplify_boxed(operand)),
+        ExprKind::Factorial(operand) => ExprKind::Factorial(simplify_boxed(operand)),
+        ExprKind::Percent(operand) => ExprKind::Percent(simplify_boxed(operand)),
+        ExprKind::BinOp { lhs, op, rhs } => ExprKind::BinOp {
+            lhs: simplify_boxed(lhs),
+            op,
+            rhs: simplify_boxed(rhs),
+        },
+        ExprKind::Call { name, args } => ExprKind::Call {
+            name,
+            args: args.into_iter().map(simplify).collect(),
+        },
+        ExprKind::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => ExprKind::Conditional {
+            cond: simplify_boxed(cond),
+            then: simplify_boxed(then),
+            otherwise: simplify_boxed(otherwise),
+        },
+        ExprKind::Assign { name, value } => ExprKind::Assign {
+            name,
+            value: simplify_boxed(value),
+        },
+        ExprKind::Let { name, value, body } => ExprKind::Let {
+            name,
+            value: simplify_boxed(value),
+            body: simplify_boxed(body),
+        },
+        ExprKind::Range { start, end } => ExprKind::Range {
+            start: simplify_boxed(start),
+            end: simplify_boxed(end),
+        },
+        kind @ (ExprKind::Integer(..)
+        | ExprKind::Float(_)
+        | ExprKind::Bool(_)
+        | ExprKind::Constant(_)
+        | ExprKind::Variable(_)) => kind,
+    };
+    Expr::new(kind, span)
+}
+
+/// Simplifies the negation spanning `span` of `operand`, along with any negations directly inside it.
+fn simplify_negations(span: Span, mut operand: Expr) -> Expr {
+    let mut negations = 1;
+    // The span of the innermost negation, for the inner one of two that are kept.
+    let mut innermost = span;
+    loop {
+        match operand.kind {
+            ExprKind::UnaryMinus(inner) => {
+                negations += 1;
+                innermost = operand.span;
+                operand = *inner;
+            }
+            kind => {
+                operand = Expr::new(kind, operand.span);
+                break;
+            }
+        }
+    }
+    let operand = simplify(operand);
+    let kind = match operand.kind {
+        // Negating an integer only fails for `Int::MIN`, whose negations are kept so that they still fail.
+        ExprKind::Integer(i, radix) if Number::checked_neg(i.clone()).is_ok() => {
+            let i = if negations % 2 == 0 { i } else { -i };
+            ExprKind::Integer(i, radix)
+        }
+        ExprKind::Float(x) => ExprKind::Float(if negations % 2 == 0 { x } else { -x }),
+        kind if negations % 2 == 1 => ExprKind::UnaryMinus(Box::new(Expr::new(kind, operand.span))),
+        kind => {
+            let negated = Expr::new(
+                ExprKind::UnaryMinus(Box::new(Expr::new(kind, operand.span))),
+                innermost,
+            );
+            ExprKind::UnaryMinus(Box::new(negated))
+        }
+    };
+    Expr::new(kind, span)
+}
+
+/// Simplifies a boxed operand, reusing its allocation.
+fn simplify_boxed(mut expr: Box<Expr>) -> Box<Expr> {
+    *expr = simplify(*expr);
+    expr
+}
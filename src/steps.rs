@@ -0,0 +1,125 @@
+use std::mem;
+
+use crate::{Environment, EvalError, Expr, ExprKind, Op, Value};
+
+/// Evaluates an expression that does not refer to any variables one reduction at a time.
+///
+/// See [`Environment::eval_steps`].
+pub fn eval_steps(expr: &Expr) -> Result<(Value, Vec<String>), EvalError> {
+    Environment::new().eval_steps(expr)
+}
+
+impl Environment {
+    /// Evaluates `expr` like [`Environment::eval`], returning each intermediate expression along with the value.
+    ///
+    /// Every step replaces the leftmost innermost node whose operands are all values by its value,
+    /// so `2 + 3 * 4` takes the steps `2 + 3 * 4`, `2 + 12` and `14`.
+    /// The first step is `expr` itself and the last one is the value. Steps that would be written the same
+    /// as the one before them, like negating a literal, are left out.
+    /// Variables and constants are replaced by their values in their own steps.
+    /// A conditional is replaced by the branch that is taken once its condition is known, and
+    /// `&&` and `||` are replaced by their left operand if that decides the result.
+    pub fn eval_steps(&mut self, expr: &Expr) -> Result<(Value, Vec<String>), EvalError> {
+        self.reset_operations();
+        let mut expr = expr.clone();
+        let mut steps = vec![expr.to_string()];
+        loop {
+            if let Some(value) = literal_value(&expr) {
+                return Ok((value, steps));
+            }
+            self.reduce(&mut expr)?;
+            // Negating a literal gives a negative literal, which is written the same.
+            let step = expr.to_string();
+            if steps.last() != Some(&step) {
+                steps.push(step);
+            }
+        }
+    }
+
+    /// Performs the first reduction in `expr`, which must not be a value yet.
+    fn reduce(&mut self, expr: &mut Expr) -> Result<(), EvalError> {
+        let operands: Vec<&mut Expr> = match &mut expr.kind {
+            ExprKind::UnaryMinus(operand)
+            | ExprKind::Not(operand)
+            | ExprKind::Factorial(operand)
+            | ExprKind::Percent(operand)
+            | ExprKind::Assign { value: operand, .. } => vec![operand],
+            // The body is evaluated in one step, once the value is known.
+            ExprKind::Let { value, .. } => vec![value],
+            ExprKind::BinOp {
+                lhs,
+                op: op @ (Op::And | Op::Or),
+                rhs,
+            } => match literal_value(lhs) {
+                Some(Value::Bool(b)) if b == matches!(op, Op::Or) => {
+                    *expr = mem::replace(lhs, placeholder());
+                    return Ok(());
+                }
+                Some(_) => vec![rhs],
+                None => vec![lhs],
+            },
+            ExprKind::BinOp { lhs, rhs, .. } => vec![lhs, rhs],
+            // The ends of a range are reduced in place, and the range is only evaluated by its function.
+            ExprKind::Call { args, .. } => args
+                .iter_mut()
+                .flat_map(|arg| match arg {
+                    Expr {
+                        kind: ExprKind::Range { start, end },
+                        ..
+                    } => vec![&mut **start, &mut **end],
+                    arg => vec![arg],
+                })
+                .collect(),
+            ExprKind::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => match literal_value(cond) {
+                Some(Value::Bool(true)) => {
+                    *expr = mem::replace(then, placeholder());
+                    return Ok(());
+                }
+                Some(Value::Bool(false)) => {
+                    *expr = mem::replace(otherwise, placeholder());
+                    return Ok(());
+                }
+                // Evaluating the whole conditional reports the type mismatch.
+                Some(_) => vec![],
+                None => vec![cond],
+            },
+            ExprKind::Integer(..)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Constant(_)
+            | ExprKind::Variable(_)
+            | ExprKind::Range { .. } => vec![],
+        };
+        if let Some(operand) = operands
+            .into_iter()
+            .find(|operand| literal_value(operand).is_none())
+        {
+            return self.reduce(operand);
+        }
+        let kind = match self.eval_node(expr)? {
+            Value::Int(i) => ExprKind::Integer(i, expr.preferred_radix()),
+            Value::Float(x) => ExprKind::Float(x),
+            Value::Bool(b) => ExprKind::Bool(b),
+        };
+        *expr = Expr::new(kind, expr.span);
+        Ok(())
+    }
+}
+
+fn literal_value(expr: &Expr) -> Option<Value> {
+    match &expr.kind {
+        ExprKind::Integer(i, _) => Some(Value::Int(i.clone())),
+        ExprKind::Float(x) => Some(Value::Float(*x)),
+        ExprKind::Bool(b) => Some(Value::Bool(*b)),
+        _ => None,
+    }
+}
+
+/// Stands in for a node that is moved out of the tree.
+fn placeholder() -> Expr {
+    ExprKind::Bool(false).into()
+}
@@ -0,0 +1,44 @@
+//! The lexical tokens of input, for tools such as editors that need less than a tree.
+
+use std::fmt;
+
+use crate::{CalcError, ParseConfig, Rule, Span};
+
+/// A token of the input: a leaf of the parse, such as a number, a name or an operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpannedToken {
+    pub rule: Rule,
+    pub span: Span,
+}
+
+impl fmt::Display for SpannedToken {
+    /// Writes the rule and the span, e.g. `integer@0..2`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}@{}..{}", self.rule, self.span.start, self.span.end)
+    }
+}
+
+/// Lists the tokens of a line of statements in order, see [`ParseConfig::tokens`].
+pub fn tokens(input: &str) -> Result<Vec<SpannedToken>, CalcError> {
+    ParseConfig::default().tokens(input)
+}
+
+impl ParseConfig {
+    /// Parses `input` as a `program` and lists the pairs that have no inner pairs, in the order they appear.
+    ///
+    /// Whitespace and comments are skipped. Parentheses, commas, prefix operators and keywords such as `let`
+    /// are matched as plain strings by the grammar, so they have no rule and are not listed either,
+    /// and neither are rules that match nothing, like the end of input.
+    pub fn tokens(&self, input: &str) -> Result<Vec<SpannedToken>, CalcError> {
+        Ok(self
+            .parse(Rule::program, input)?
+            .flatten()
+            .filter(|pair| pair.clone().into_inner().next().is_none())
+            .map(|pair| SpannedToken {
+                rule: pair.as_rule(),
+                span: pair.as_span().into(),
+            })
+            .filter(|token| token.span.start < token.span.end)
+            .collect())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::format::format_int;
+use crate::{walk, Expr, ExprKind, ExprVisitor};
+
+/// Renders an expression as an indented tree, with one node per line and its operands below it.
+///
+/// For example `2 + 3 * 4` becomes:
+///
+/// ```text
+/// Add
+/// ├─ 2
+/// └─ Multiply
+///    ├─ 3
+///    └─ 4
+/// ```
+pub fn render_tree(expr: &Expr) -> String {
+    let mut out = String::new();
+    write_node(&mut out, expr, "", "");
+    out
+}
+
+/// Writes `expr` on a line starting with `prefix`, and its children indented by `indent`.
+fn write_node(out: &mut String, expr: &Expr, prefix: &str, indent: &str) {
+    out.push_str(prefix);
+    out.push_str(&label(expr));
+    out.push('\n');
+    let children = children(expr);
+    for (i, child) in children.iter().enumerate() {
+        let (branch, continuation) = if i + 1 < children.len() {
+            ("├─ ", "│  ")
+        } else {
+            ("└─ ", "   ")
+        };
+        let child_prefix = format!("{}{}", indent, branch);
+        write_node(
+            out,
+            child,
+            &child_prefix,
+            &format!("{}{}", indent, continuation),
+        );
+    }
+}
+
+fn label(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Integer(i, radix) => format_int(i, *radix),
+        ExprKind::Float(x) => format!("{:?}", x),
+        ExprKind::Bool(b) => b.to_string(),
+        ExprKind::Constant(constant) => constant.name().to_owned(),
+        ExprKind::Variable(name) => name.clone(),
+        ExprKind::Call { name, .. } => format!("Call {}", name),
+        ExprKind::UnaryMinus(_) => "UnaryMinus".to_owned(),
+        ExprKind::Not(_) => "Not".to_owned(),
+        ExprKind::Factorial(_) => "Factorial".to_owned(),
+        ExprKind::Percent(_) => "Percent".to_owned(),
+        ExprKind::BinOp { op, .. } => format!("{:?}", op),
+        ExprKind::Conditional { .. } => "Conditional".to_owned(),
+        ExprKind::Assign { name, .. } => format!("Assign {}", name),
+        ExprKind::Let { name, .. } => format!("Let {}", name),
+        ExprKind::Range { .. } => "Range".to_owned(),
+    }
+}
+
+/// The direct operands of `expr`, in source order.
+fn children(expr: &Expr) -> Vec<&Expr> {
+    struct Children<'a>(Vec<&'a Expr>);
+
+    impl<'a> ExprVisitor<'a> for Children<'a> {
+        // Collects the operands without walking into them.
+        fn visit_expr(&mut self, expr: &'a Expr) {
+            self.0.push(expr);
+        }
+    }
+
+    let mut children = Children(Vec::new());
+    walk(&mut children, expr);
+    children.0
+}
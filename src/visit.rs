@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use crate::{Constant, Expr, ExprKind, Int, Op, Radix};
+
+/// A read-only traversal of an expression tree.
+///
+/// [`walk`] calls the method for the kind of node it is given. The default implementations of the
+/// methods for nodes with operands call [`visit_expr`](ExprVisitor::visit_expr) on each operand in
+/// source order, and the defaults for leaves do nothing, so a visitor only has to override the
+/// methods for the nodes it is interested in. An override that should still look at the operands
+/// has to visit them itself.
+///
+/// Override `visit_expr` to see every node, including its span, before or after calling [`walk`].
+/// The lifetime `'a` lets a visitor keep references to the nodes it visits.
+pub trait ExprVisitor<'a> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        walk(self, expr);
+    }
+
+    fn visit_integer(&mut self, _value: Int, _radix: Radix) {}
+
+    fn visit_float(&mut self, _value: f64) {}
+
+    fn visit_bool(&mut self, _value: bool) {}
+
+    fn visit_constant(&mut self, _constant: Constant) {}
+
+    fn visit_variable(&mut self, _name: &'a str) {}
+
+    fn visit_call(&mut self, _name: &'a str, args: &'a [Expr]) {
+        for arg in args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_unary_minus(&mut self, operand: &'a Expr) {
+        self.visit_expr(operand);
+    }
+
+    fn visit_not(&mut self, operand: &'a Expr) {
+        self.visit_expr(operand);
+    }
+
+    fn visit_factorial(&mut self, operand: &'a Expr) {
+        self.visit_expr(operand);
+    }
+
+    fn visit_percent(&mut self, operand: &'a Expr) {
+        self.visit_expr(operand);
+    }
+
+    fn visit_binop(&mut self, lhs: &'a Expr, _op: &'a Op, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    fn visit_conditional(&mut self, cond: &'a Expr, then: &'a Expr, otherwise: &'a Expr) {
+        self.visit_expr(cond);
+        self.visit_expr(then);
+        self.visit_expr(otherwise);
+    }
+
+    fn visit_assign(&mut self, _name: &'a str, value: &'a Expr) {
+        self.visit_expr(value);
+    }
+
+    fn visit_let(&mut self, _name: &'a str, value: &'a Expr, body: &'a Expr) {
+        self.visit_expr(value);
+        self.visit_expr(body);
+    }
+
+    fn visit_range(&mut self, start: &'a Expr, end: &'a Expr) {
+        self.visit_expr(start);
+        self.visit_expr(end);
+    }
+}
+
+/// Calls the method of `visitor` for the kind of `expr`.
+pub fn walk<'a, V: ExprVisitor<'a> + ?Sized>(visitor: &mut V, expr: &'a Expr) {
+    match &expr.kind {
+        ExprKind::Integer(i, radix) => visitor.visit_integer(i.clone(), *radix),
+        ExprKind::Float(x) => visitor.visit_float(*x),
+        ExprKind::Bool(b) => visitor.visit_bool(*b),
+        ExprKind::Constant(constant) => visitor.visit_constant(*constant),
+        ExprKind::Variable(name) => visitor.visit_variable(name),
+        ExprKind::Call { name, args } => visitor.visit_call(name, args),
+        ExprKind::UnaryMinus(operand) => visitor.visit_unary_minus(operand),
+        ExprKind::Not(operand) => visitor.visit_not(operand),
+        ExprKind::Factorial(operand) => visitor.visit_factorial(operand),
+        ExprKind::Percent(operand) => visitor.visit_percent(operand),
+        ExprKind::BinOp { lhs, op, rhs } => visitor.visit_binop(lhs, op, rhs),
+        ExprKind::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => visitor.visit_conditional(cond, then, otherwise),
+        ExprKind::Assign { name, value } => visitor.visit_assign(name, value),
+        ExprKind::Let { name, value, body } => visitor.visit_let(name, value, body),
+        ExprKind::Range { start, end } => visitor.visit_range(start, end),
+    }
+}
+
+impl Expr {
+    /// Whether the value of this expression is the same in every environment, because it refers to
+    /// no variables, calls no functions and assigns nothing. The constants `pi` and `e` are allowed.
+    pub fn is_constant(&self) -> bool {
+        struct IsConstant(bool);
+
+        impl<'a> ExprVisitor<'a> for IsConstant {
+            fn visit_variable(&mut self, _name: &'a str) {
+                self.0 = false;
+            }
+
+            fn visit_call(&mut self, _name: &'a str, _args: &'a [Expr]) {
+                self.0 = false;
+            }
+
+            fn visit_assign(&mut self, _name: &'a str, _value: &'a Expr) {
+                self.0 = false;
+            }
+        }
+
+        let mut visitor = IsConstant(true);
+        visitor.visit_expr(self);
+        visitor.0
+    }
+
+    /// The names of all variables this expression reads, which have to be defined to evaluate it.
+    ///
+    /// Names that are only assigned to are not included, so for `x = y + 1` this is just `y`,
+    /// and neither are names bound by a `let` in its body, so for `let x = 2 in x * y` it is also `y`.
+    pub fn variables(&self) -> HashSet<String> {
+        struct Variables(HashSet<String>);
+
+        impl<'a> ExprVisitor<'a> for Variables {
+            fn visit_variable(&mut self, name: &'a str) {
+                self.0.insert(name.to_owned());
+            }
+
+            fn visit_let(&mut self, name: &'a str, value: &'a Expr, body: &'a Expr) {
+                self.visit_expr(value);
+                let mut body_variables = body.variables();
+                body_variables.remove(name);
+                self.0.extend(body_variables);
+            }
+        }
+
+        let mut visitor = Variables(HashSet::new());
+        visitor.visit_expr(self);
+        visitor.0
+    }
+}
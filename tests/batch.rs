@@ -0,0 +1,16 @@
+use pest_calculator::{eval_all, CalcError, Value};
+
+#[test]
+fn eval_all_returns_one_result_per_input() {
+    let results = eval_all(&["1 + 2", "2 * 2.5", "1 +", "3 > 2"]);
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), &Value::Int(3));
+    assert_eq!(results[1].as_ref().unwrap(), &Value::Float(5.0));
+    assert!(matches!(results[2], Err(CalcError::Parse(_))));
+    assert_eq!(results[3].as_ref().unwrap(), &Value::Bool(true));
+}
+
+#[test]
+fn eval_all_of_nothing_is_empty() {
+    assert!(eval_all(&[]).is_empty());
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "bignum")]
+
+mod common;
+
+use common::parse;
+use pest_calculator::{
+    eval_as, evaluate, evaluate_to_string, format_result, CalcError, EvalError, EvalErrorKind, Int,
+    Radix, Value,
+};
+
+fn int(digits: &str) -> Value {
+    Value::Int(digits.parse::<Int>().unwrap())
+}
+
+#[test]
+fn factorials_do_not_overflow() {
+    assert_eq!(
+        evaluate("50!").unwrap(),
+        int("30414093201713378043612608166064768844377641568960512000000000000")
+    );
+}
+
+#[test]
+fn powers_do_not_overflow() {
+    assert_eq!(
+        evaluate("2 ^ 100").unwrap(),
+        int("1267650600228229401496703205376")
+    );
+    assert_eq!(evaluate("(-3) ^ 41").unwrap(), int("-36472996377170786403"));
+    // Negative exponents are still computed as floats.
+    assert_eq!(evaluate("2 ^ -1").unwrap(), Value::Float(0.5));
+}
+
+#[test]
+fn the_other_operators_do_not_overflow_either() {
+    for (input, expected) in [
+        ("9223372036854775807 + 1", "9223372036854775808"),
+        ("-9223372036854775807 - 2", "-9223372036854775809"),
+        ("-(-9223372036854775807 - 1)", "9223372036854775808"),
+        ("abs(-9223372036854775807 - 1)", "9223372036854775808"),
+        (
+            "99999999999999999999 * 99999999999999999999",
+            "9999999999999999999800000000000000000001",
+        ),
+        ("1 << 100", "1267650600228229401496703205376"),
+        ("(1 << 100) >> 99", "2"),
+        ("product(1..25)", "15511210043330985984000000"),
+    ] {
+        assert_eq!(evaluate(input).unwrap(), int(expected), "{}", input);
+    }
+}
+
+#[test]
+fn literals_can_be_arbitrarily_large() {
+    assert_eq!(
+        evaluate("123456789012345678901234567890 + 0").unwrap(),
+        int("123456789012345678901234567890")
+    );
+    assert_eq!(
+        evaluate("0xffffffffffffffffffffffff").unwrap(),
+        int("79228162514264337593543950335")
+    );
+}
+
+#[test]
+fn division_rounds_like_fixed_width_integers() {
+    for (input, expected) in [
+        ("-7 / 2", "-3"),
+        ("-7 // 2", "-4"),
+        ("7 // -2", "-4"),
+        ("-7 % 3", "-1"),
+        ("7 % -3", "1"),
+    ] {
+        assert_eq!(evaluate(input).unwrap(), int(expected), "{}", input);
+    }
+    match evaluate("(2 ^ 100) / 0") {
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        })) => {}
+        other => panic!("expected a division by zero, found {:?}", other),
+    }
+}
+
+#[test]
+fn large_results_are_printed_in_every_radix() {
+    let value = evaluate("-(2 ^ 70)").unwrap();
+    assert_eq!(evaluate_to_string("2 ^ 70"), "1180591620717411303424");
+    assert_eq!(
+        format_result(&value, Radix::Hexadecimal).unwrap(),
+        "-0x400000000000000000"
+    );
+    assert_eq!(
+        format_result(&value, Radix::Binary).unwrap(),
+        format!("-0b1{}", "0".repeat(70))
+    );
+}
+
+#[test]
+fn large_integers_only_convert_to_fixed_width_if_they_fit() {
+    assert_eq!(eval_as::<i64>(&parse("2 ^ 62")).unwrap(), 1 << 62);
+    assert!(matches!(
+        eval_as::<i64>(&parse("9223372036854775808")),
+        Err(EvalError {
+            kind: EvalErrorKind::Overflow("conversion"),
+            ..
+        })
+    ));
+    assert_eq!(
+        eval_as::<Int>(&parse("2 ^ 64 // 3")).unwrap(),
+        "6148914691236517205".parse::<Int>().unwrap()
+    );
+    assert_eq!(
+        eval_as::<f64>(&parse("2 ^ 64")).unwrap(),
+        18446744073709551616.0
+    );
+}
@@ -0,0 +1,69 @@
+use pest_calculator::{evaluate, CalcError, EvalError, EvalErrorKind, Int, Value};
+
+fn int(input: &str) -> Int {
+    match evaluate(input) {
+        Ok(Value::Int(i)) => i,
+        other => panic!("expected an integer for {:?}, found {:?}", input, other),
+    }
+}
+
+#[test]
+fn and_or_xor() {
+    assert_eq!(int("12 & 10"), 8);
+    assert_eq!(int("12 | 10"), 14);
+    assert_eq!(int("12 ~ 10"), 6);
+    assert_eq!(int("0xFF & -2"), 0xFE);
+}
+
+#[test]
+fn shifts() {
+    assert_eq!(int("1 << 4"), 16);
+    assert_eq!(int("-16 >> 2"), -4);
+    assert_eq!(int("1 << 63"), Int::MIN);
+}
+
+#[test]
+fn precedence() {
+    // Shifts bind looser than arithmetic, and `&` tighter than `~` tighter than `|`.
+    assert_eq!(int("1 << 2 + 1"), 8);
+    assert_eq!(int("1 | 6 & 3"), 3);
+    assert_eq!(int("1 | 2 ~ 3"), 1);
+    assert_eq!(evaluate("1 | 2 == 3").unwrap(), Value::Bool(true));
+    assert_eq!(evaluate("1 & 3 == 1 && true").unwrap(), Value::Bool(true));
+}
+
+#[test]
+fn invalid_shift() {
+    for (input, amount) in [("1 << 64", 64), ("1 >> -1", -1)] {
+        match evaluate(input) {
+            Err(CalcError::Eval(EvalError {
+                kind: EvalErrorKind::InvalidShift(a),
+                ..
+            })) => assert_eq!(a, amount),
+            other => panic!(
+                "expected an invalid shift for {:?}, found {:?}",
+                input, other
+            ),
+        }
+    }
+}
+
+#[test]
+fn requires_integers() {
+    for input in ["1.5 & 1", "1 | true", "2 << 1.0"] {
+        assert!(
+            matches!(
+                evaluate(input),
+                Err(CalcError::Eval(EvalError {
+                    kind: EvalErrorKind::TypeMismatch {
+                        expected: "int",
+                        ..
+                    },
+                    ..
+                }))
+            ),
+            "{:?} should be a type mismatch",
+            input
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{canonicalize, Expr};
+
+fn canonical(input: &str) -> Expr {
+    canonicalize(parse(input))
+}
+
+fn same(a: &str, b: &str) -> bool {
+    canonical(a).structurally_eq(&canonical(b))
+}
+
+#[test]
+fn reordered_sums_and_products_are_equal() {
+    assert!(same("3 + 1 + 2", "1 + 2 + 3"));
+    assert!(same("(2 + 1) + 3", "1 + (3 + 2)"));
+    assert!(same("x * 2 * y", "y * (x * 2)"));
+    assert!(same("a * b + c", "c + b * a"));
+    assert_eq!(canonical("3 + 1 + 2").to_string(), "1 + 2 + 3");
+    assert_eq!(canonical("c + (b + a) * 2").to_string(), "2 * (a + b) + c");
+}
+
+#[test]
+fn only_chains_of_the_same_operator_are_flattened() {
+    assert!(!same("1 + 2 * 3", "1 * 2 + 3"));
+    assert_eq!(canonical("3 * (2 + 1)").to_string(), "(1 + 2) * 3");
+    assert!(!same("x + y", "x * y"));
+}
+
+#[test]
+fn other_operators_keep_their_operands_in_place() {
+    assert!(!same("3 - 1", "1 - 3"));
+    assert!(!same("x / 2", "2 / x"));
+    assert!(!same("7 % 3", "3 % 7"));
+    assert!(!same("1 - 2 - 3", "1 - (2 - 3)"));
+    assert!(!same("2 ^ 3", "3 ^ 2"));
+    assert_eq!(canonical("(y + x) - (b * a)").to_string(), "x + y - a * b");
+}
+
+#[test]
+fn canonicalizes_inside_every_node() {
+    assert!(same(
+        "max(b + a, 1) ? -(y * x) : 2",
+        "max(a + b, 1) ? -(x * y) : 2"
+    ));
+    assert!(same("let v = 2 + 1 in v * w", "let v = 1 + 2 in w * v"));
+    assert!(same("sum(2 + 1..n + 1)", "sum(1 + 2..1 + n)"));
+}
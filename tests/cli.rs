@@ -0,0 +1,572 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the calculator binary with `args`, feeding `input` on stdin.
+fn run_with_args(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pest-calculator"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start calculator");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Returns the remainder of every line in `output` that starts with `prefix`.
+fn lines_with_prefix(output: &[u8], prefix: &str) -> Vec<String> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix))
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Collects the `Result: ...` lines printed for each input line.
+fn results(input: &str) -> Vec<String> {
+    lines_with_prefix(&run_with_args(&[], input).stdout, "Result: ")
+}
+
+/// Collects the `Error: ...` lines reported for each input line.
+fn errors(input: &str) -> Vec<String> {
+    lines_with_prefix(&run_with_args(&[], input).stderr, "Error: ")
+}
+
+/// Writes `contents` to a file in the temporary directory that is unique to this test.
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("pest-calculator-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn evaluates_precedence() {
+    assert_eq!(results("2 + 3 * 4\n"), ["14"]);
+    assert_eq!(results("(2 + 3) * 4\n"), ["20"]);
+}
+
+#[test]
+fn evaluates_unary_minus() {
+    assert_eq!(results("-(5 - 2)\n"), ["-3"]);
+    assert_eq!(results("-2 * -(3 + 1)\n"), ["8"]);
+}
+
+#[test]
+fn evaluates_integer_division_and_modulo() {
+    assert_eq!(results("7 / 2\n"), ["3"]);
+    assert_eq!(results("-7 % 3\n"), ["-1"]);
+    assert_eq!(results("10 - 4 - 3\n"), ["3"]);
+}
+
+#[test]
+fn reports_division_by_zero() {
+    assert_eq!(errors("10 / 0\n"), ["division by zero"]);
+    assert_eq!(errors("7 % 0\n"), ["division by zero"]);
+}
+
+#[test]
+fn underlines_the_failing_subexpression() {
+    let output = run_with_args(&[], "1 + (6 / (2 - 2))\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        [
+            "Error: division by zero",
+            "  1 + (6 / (2 - 2))",
+            "      ^^^^^^^^^^^^^",
+        ]
+    );
+}
+
+#[test]
+fn keeps_reading_after_an_error() {
+    assert_eq!(results("1 / 0\n1 + 1\n"), ["2"]);
+}
+
+#[test]
+fn reports_overflow() {
+    assert_eq!(
+        errors("9000000000000000000 + 9000000000000000000\n"),
+        ["integer overflow in addition"]
+    );
+    assert_eq!(
+        errors("4294967296 * 4294967296\n"),
+        ["integer overflow in multiplication"]
+    );
+    // i64::MIN can only be written as a negated expression, since 9223372036854775808 is not a valid literal.
+    assert_eq!(
+        errors("-(-9223372036854775807 - 1)\n"),
+        ["integer overflow in negation"]
+    );
+}
+
+#[test]
+fn evaluates_floats() {
+    assert_eq!(results("3.14\n"), ["3.14"]);
+    assert_eq!(results("1e3\n"), ["1000.0"]);
+    assert_eq!(results("2.5E-1\n"), ["0.25"]);
+}
+
+#[test]
+fn promotes_mixed_operands_to_float() {
+    assert_eq!(results("2 + 3.5\n"), ["5.5"]);
+    assert_eq!(results("7 / 2.0\n"), ["3.5"]);
+    assert_eq!(results("-1.5 * 2\n"), ["-3.0"]);
+    assert_eq!(errors("1.5 / 0\n"), ["division by zero"]);
+}
+
+#[test]
+fn power_is_right_associative() {
+    assert_eq!(results("2 ^ 3 ^ 2\n"), ["512"]);
+    assert_eq!(results("(2 ^ 3) ^ 2\n"), ["64"]);
+}
+
+#[test]
+fn power_binds_tighter_than_multiplication() {
+    assert_eq!(results("2 * 3 ^ 2\n"), ["18"]);
+    assert_eq!(results("3 ^ 2 * 2\n"), ["18"]);
+    assert_eq!(results("2 ^ 2 + 1\n"), ["5"]);
+}
+
+#[test]
+fn power_edge_cases() {
+    assert_eq!(results("2 ^ -1\n"), ["0.5"]);
+    assert_eq!(results("4 ^ 0.5\n"), ["2.0"]);
+    assert_eq!(errors("2 ^ 63\n"), ["integer overflow in exponentiation"]);
+}
+
+#[test]
+fn evaluates_factorial() {
+    assert_eq!(results("0!\n"), ["1"]);
+    assert_eq!(results("5!\n"), ["120"]);
+    assert_eq!(results("3!!\n"), ["720"]);
+    assert_eq!(results("2 * 3! ^ 2\n"), ["72"]);
+    assert_eq!(results("-3!\n"), ["-6"]);
+}
+
+#[test]
+fn factorial_errors() {
+    assert_eq!(errors("(-3)!\n"), ["factorial of a negative number"]);
+    assert_eq!(errors("21!\n"), ["integer overflow in factorial"]);
+    assert_eq!(errors("2.5!\n"), ["factorial of a non-integer"]);
+}
+
+#[test]
+fn reports_literal_out_of_range() {
+    assert_eq!(
+        errors("99999999999999999999\n1 + 1\n"),
+        ["literal 99999999999999999999 is out of range"]
+    );
+    assert_eq!(results("99999999999999999999\n1 + 1\n"), ["2"]);
+}
+
+#[test]
+fn variables_persist_across_lines() {
+    assert_eq!(results("x = 5\nx * 2\n"), ["5", "10"]);
+    assert_eq!(errors("x * 2\n"), ["undefined variable x"]);
+}
+
+#[test]
+fn evaluates_semicolon_separated_statements() {
+    assert_eq!(results("1 + 2; 3 * 4\n"), ["3", "12"]);
+    assert_eq!(results("1; 1 / 0; 2;\n"), ["1", "2"]);
+    assert_eq!(errors("1; 1 / 0; 2;\n"), ["division by zero"]);
+}
+
+#[test]
+fn evaluates_files_in_order() {
+    let first = temp_file("first.txt", "x = 2\n1 + 2\n");
+    let second = temp_file("second.txt", "x * 10\n");
+    let output = run_with_args(&[first.to_str().unwrap(), second.to_str().unwrap()], "");
+    fs::remove_file(first).unwrap();
+    fs::remove_file(second).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Result: "),
+        ["2", "3", "20"]
+    );
+}
+
+#[test]
+fn reports_missing_file() {
+    let output = run_with_args(&["/nonexistent/expressions.txt"], "");
+    assert!(!output.status.success());
+    let errors = lines_with_prefix(&output.stderr, "Error: ");
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0].starts_with("cannot read /nonexistent/expressions.txt:"),
+        "unexpected error: {}",
+        errors[0]
+    );
+}
+
+#[test]
+fn parse_errors_name_the_file_and_line() {
+    let path = temp_file("malformed.txt", "1 + 2\n1 + \n");
+    let output = run_with_args(&[path.to_str().unwrap()], "");
+    fs::remove_file(&path).unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<_> = stderr.lines().collect();
+    assert_eq!(lines[0], "Error: parse error");
+    assert_eq!(lines[1], format!(" --> {}:2:5", path.display()));
+    assert_eq!(lines[3], "2 | 1 + ");
+}
+
+#[test]
+fn parse_errors_name_misplaced_operators() {
+    let output = run_with_args(&[], "+5\n5+\n5 * * 3\n");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "  = "),
+        [
+            "expression starts with a binary operator",
+            "expression ends with an operator",
+            "two operators in a row",
+        ]
+    );
+}
+
+#[test]
+fn rejects_deeply_nested_input() {
+    let input = format!("{}1{}\n1 + 1\n", "(".repeat(10_000), ")".repeat(10_000));
+    assert_eq!(
+        errors(&input),
+        ["expression is nested more than 64 levels deep"]
+    );
+    assert_eq!(results(&input), ["2"]);
+}
+
+#[test]
+fn prints_json() {
+    let output = run_with_args(
+        &["--json"],
+        "1+2; 1/0\n2.5 * 2\n(1)  +  2 # sum\n\"x\" \\ 1\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let objects: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line should be a JSON object"))
+        .collect();
+    assert_eq!(objects.len(), 5, "{}", stdout);
+    assert_eq!(
+        objects[..4],
+        [
+            serde_json::json!({"input": "1+2", "result": 3}),
+            serde_json::json!({"input": "1/0", "error": "division by zero"}),
+            serde_json::json!({"input": "2.5 * 2", "result": 5.0}),
+            serde_json::json!({"input": "(1)  +  2", "result": 3}),
+        ]
+    );
+    // Quotes, backslashes and the newlines of parse errors are escaped.
+    assert_eq!(objects[4]["input"], r#""x" \ 1"#);
+    assert!(objects[4]["error"].as_str().unwrap().contains('\n'));
+}
+
+#[test]
+fn prints_json_for_parse_errors() {
+    let output = run_with_args(&["--json"], "1 +\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with(r#"{"input": "1 +", "error": ""#),
+        "unexpected output: {}",
+        stdout
+    );
+    assert_eq!(stdout.lines().count(), 1);
+}
+
+#[test]
+fn prints_trees() {
+    let output = run_with_args(&["--tree"], "1 - -2\n");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Subtract\n├─ 1\n└─ UnaryMinus\n   └─ 2\nResult: 3\n"
+    );
+}
+
+#[test]
+fn rejects_unknown_options() {
+    let output = run_with_args(&["--frobnicate"], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["unknown option --frobnicate"]
+    );
+}
+
+#[test]
+fn prints_results_in_the_requested_radix() {
+    let output = run_with_args(&["--radix", "hex"], "255\n-16\n");
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Result: "),
+        ["0xff", "-0x10"]
+    );
+}
+
+#[test]
+fn rejects_floats_in_hex() {
+    let output = run_with_args(&["--radix", "hex"], "2.5\n");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["cannot print the float 2.5 in hexadecimal"]
+    );
+}
+
+#[test]
+fn rejects_unknown_radix() {
+    let output = run_with_args(&["--radix", "roman"], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["unknown radix roman"]
+    );
+}
+
+#[test]
+fn implicit_multiplication_is_opt_in() {
+    let output = run_with_args(&["--implicit-multiplication"], "2(3 + 4)\n");
+    assert_eq!(lines_with_prefix(&output.stdout, "Result: "), ["14"]);
+    assert!(results("2(3 + 4)\n").is_empty());
+}
+
+#[test]
+fn float_division_is_opt_in() {
+    let input = "7 / 2\n7 // 2\n-7 / 2\n-7 // 2\n";
+    assert_eq!(results(input), ["3", "3", "-3", "-4"]);
+    let output = run_with_args(&["--float-division"], input);
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Result: "),
+        ["3.5", "3", "-3.5", "-4"]
+    );
+}
+
+#[test]
+fn ans_is_a_variable_outside_of_an_interactive_session() {
+    assert_eq!(errors("2 + 3\nans * 10\n"), ["undefined variable ans"]);
+    assert_eq!(results("ans = 4\nans * 10\n"), ["4", "40"]);
+    let output = run_with_args(&["--expr", "2 + 3", "--expr", "ans"], "");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["undefined variable ans"]
+    );
+}
+
+#[test]
+fn prints_evaluation_steps() {
+    let output = run_with_args(&["--steps"], "2 + 3 * 4\n");
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Step: "),
+        ["2 + 3 * 4", "2 + 12", "14"]
+    );
+    assert_eq!(lines_with_prefix(&output.stdout, "Result: "), ["14"]);
+    assert!(lines_with_prefix(&output.stdout, "Parsed: ").is_empty());
+}
+
+#[test]
+fn prints_stats_after_all_lines() {
+    let output = run_with_args(&["--stats"], "1 + 1\n1 / 0\n2 +\n3; 4\n");
+    let stats = lines_with_prefix(&output.stderr, "Stats: ");
+    assert_eq!(stats.len(), 1);
+    assert!(
+        stats[0].starts_with("4 lines, 3 parsed, 2 failed, "),
+        "{}",
+        stats[0]
+    );
+    assert!(stats[0].ends_with(" per line"), "{}", stats[0]);
+}
+
+#[test]
+fn prints_no_stats_by_default() {
+    let output = run_with_args(&[], "1 + 1\n");
+    assert!(lines_with_prefix(&output.stderr, "Stats: ").is_empty());
+}
+
+#[test]
+fn prints_results_in_the_radix_of_the_literals() {
+    assert_eq!(
+        results("0xFF + 1\n255 + 1\n0xff / 2.0\n"),
+        ["0x100", "256", "127.5"]
+    );
+    let output = run_with_args(&["--radix", "dec"], "0xFF + 1\n");
+    assert_eq!(lines_with_prefix(&output.stdout, "Result: "), ["256"]);
+}
+
+#[test]
+fn check_reports_syntax_errors_without_evaluating() {
+    let path = temp_file("check.txt", "1 + 2\n1 / 0\n2 +\nx * y\n(1\n");
+    let output = run_with_args(&["--check", path.to_str().unwrap()], "");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let errors: Vec<_> = lines_with_prefix(&output.stderr, " --> ")
+        .into_iter()
+        .map(|location| location.rsplit_once(':').unwrap().0.to_owned())
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            format!("{}:3", path.display()),
+            format!("{}:5", path.display())
+        ]
+    );
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn check_succeeds_if_every_line_parses() {
+    let output = run_with_args(&["--check"], "1 / 0\nundefined + 1\n");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn groups_digits_of_decimal_results() {
+    let input = "1000000\n-1234.5\n255\n0xfffff\n";
+    assert_eq!(results(input), ["1000000", "-1234.5", "255", "0xfffff"]);
+    let output = run_with_args(&["--group"], input);
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Result: "),
+        ["1,000,000", "-1,234.5", "255", "0xfffff"]
+    );
+    let output = run_with_args(&["--group-separator", "_"], input);
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Result: "),
+        ["1_000_000", "-1_234.5", "255", "0xfffff"]
+    );
+
+    let output = run_with_args(&["--group-separator", "."], "");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn skips_blank_lines() {
+    let output = run_with_args(&[], "\n   \n1 + 1\n\n");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert_eq!(lines_with_prefix(&output.stdout, "Result: "), ["2"]);
+
+    // Skipped lines still count for the line numbers of errors.
+    let output = run_with_args(&[], "  \n1 +\n");
+    assert_eq!(lines_with_prefix(&output.stderr, "Error: ").len(), 1);
+    assert_eq!(lines_with_prefix(&output.stderr, " --> "), ["2:4"]);
+}
+
+#[test]
+fn echoes_highlighted_input_when_color_is_forced() {
+    let output = run_with_args(&["--color=always"], "2 * (3 + 4)\n");
+    assert_eq!(
+        lines_with_prefix(&output.stdout, "Input: "),
+        ["\x1b[36m2\x1b[0m \x1b[33m*\x1b[0m \x1b[2m(\x1b[0m\x1b[36m3\x1b[0m \x1b[33m+\x1b[0m \x1b[36m4\x1b[0m\x1b[2m)\x1b[0m"]
+    );
+    assert_eq!(lines_with_prefix(&output.stdout, "Result: "), ["14"]);
+
+    // Piped output is not a terminal, so `auto` uses no colors.
+    for args in [&[][..], &["--color", "auto"], &["--color", "never"]] {
+        let output = run_with_args(args, "2 * (3 + 4)\n");
+        assert!(lines_with_prefix(&output.stdout, "Input: ").is_empty());
+        assert!(!output.stdout.contains(&b'\x1b'));
+    }
+    let output = run_with_args(&["--color=sometimes"], "1\n");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["unknown color mode sometimes"]
+    );
+}
+
+#[test]
+fn evaluates_expressions_given_as_arguments() {
+    let output = run_with_args(&["--expr", "x = 2 + 3 * 4", "--expr", "x * 2"], "");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "14\n28\n");
+
+    // Each expression is one input, even if it spans several lines.
+    let output = run_with_args(&["--expr", "max(1,\n2) *\n3", "--expr", "1; 2"], "");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "6\n1\n2\n");
+
+    let output = run_with_args(&["--json", "--expr", "x = 2", "--expr", "x ^ 10"], "");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{\"input\": \"x = 2\", \"result\": 2}\n{\"input\": \"x ^ 10\", \"result\": 1024}\n"
+    );
+}
+
+#[test]
+fn fails_for_an_invalid_expression_argument() {
+    let output = run_with_args(&["--expr", "2 +", "--expr", "1 + 1"], "");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["parse error"]
+    );
+
+    let output = run_with_args(&["--expr", "1 / 0"], "");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["division by zero"]
+    );
+
+    let output = run_with_args(&["--expr"], "");
+    assert!(!output.status.success());
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["--expr needs a value"]
+    );
+}
+
+#[test]
+fn expression_arguments_only_print_their_results() {
+    let output = run_with_args(&["--expr", "2 + 3 * 4"], "");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "14\n");
+
+    // The tree is still shown when asked for.
+    let output = run_with_args(&["--tree", "--expr", "1 + 2"], "");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Add\n├─ 1\n└─ 2\n3\n"
+    );
+
+    let output = run_with_args(&["--radix", "hex", "--group", "--expr", "255"], "");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0xff\n");
+}
+
+#[test]
+fn results_that_cannot_be_formatted_fail() {
+    let output = run_with_args(&["--radix", "hex", "--expr", "1.5"], "");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["cannot print the float 1.5 in hexadecimal"]
+    );
+
+    let output = run_with_args(&["--radix", "hex", "--expr", "1.5", "--expr", "15"], "");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0xf\n");
+}
+
+#[test]
+fn empty_expression_arguments_fail() {
+    for expr in ["", "  ", "# just a note", "/* nothing */"] {
+        let output = run_with_args(&["--expr", expr], "");
+        assert!(!output.status.success(), "{:?} succeeded", expr);
+        assert!(output.stdout.is_empty(), "{:?} printed a result", expr);
+    }
+    let output = run_with_args(&["--expr", ""], "");
+    assert_eq!(
+        lines_with_prefix(&output.stderr, "Error: "),
+        ["--expr needs a non-empty expression"]
+    );
+}
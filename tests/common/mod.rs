@@ -0,0 +1,22 @@
+//! Helpers shared by the integration tests, each of which uses only some of them.
+#![allow(dead_code)]
+
+use pest::Parser;
+use pest_calculator::{parse_expr, parse_program, CalcError, CalculatorParser, Expr, Rule};
+
+/// Parses `input` as a single expression, which may also be an assignment.
+pub fn try_parse(input: &str) -> Result<Expr, CalcError> {
+    let pairs = CalculatorParser::parse(Rule::equation, input)?;
+    parse_expr(pairs)
+}
+
+/// Parses `input` like [`try_parse`], panicking if it fails.
+pub fn parse(input: &str) -> Expr {
+    try_parse(input).expect("input should parse")
+}
+
+/// Parses `input` as a program of statements separated by `;`, panicking if it fails.
+pub fn statements(input: &str) -> Vec<Expr> {
+    let pairs = CalculatorParser::parse(Rule::program, input).expect("input should parse");
+    parse_program(pairs).expect("literals should be in range")
+}
@@ -0,0 +1,115 @@
+use pest_calculator::{evaluate, CalcError, EvalError, EvalErrorKind, Value};
+
+fn truth(input: &str) -> bool {
+    match evaluate(input) {
+        Ok(Value::Bool(b)) => b,
+        other => panic!("expected a bool for {:?}, found {:?}", input, other),
+    }
+}
+
+#[test]
+fn less_and_greater() {
+    assert!(truth("3 < 5"));
+    assert!(!truth("5 < 5"));
+    assert!(truth("5 <= 5"));
+    assert!(truth("6 > 5.5"));
+    assert!(!truth("5 > 5"));
+    assert!(truth("5 >= 5"));
+}
+
+#[test]
+fn equality() {
+    assert!(truth("2 + 2 == 4"));
+    assert!(truth("2 == 2.0"));
+    assert!(truth("1 != 2"));
+    assert!(!truth("3! != 6"));
+    assert!(truth("1 < 2 == 2 < 3"));
+}
+
+#[test]
+fn binds_looser_than_arithmetic() {
+    assert!(truth("1 + 2 * 3 > 6"));
+    assert!(truth("2 ^ 3 == 8"));
+}
+
+#[test]
+fn type_mismatch() {
+    for input in [
+        "1 < 2 < 3",
+        "(1 < 2) == 1",
+        "(1 < 2) + 1",
+        "-(1 < 2)",
+        "abs(1 < 2)",
+    ] {
+        assert!(
+            matches!(
+                evaluate(input),
+                Err(CalcError::Eval(EvalError {
+                    kind: EvalErrorKind::TypeMismatch { .. },
+                    ..
+                }))
+            ),
+            "{:?} should be a type mismatch",
+            input
+        );
+    }
+}
+
+#[test]
+fn boolean_literals() {
+    assert!(truth("true"));
+    assert!(!truth("false"));
+    assert!(truth("true == (1 < 2)"));
+}
+
+#[test]
+fn logical_operators() {
+    assert!(truth("1 < 2 && 3 > 2"));
+    assert!(!truth("1 < 2 && 3 < 2"));
+    assert!(truth("1 > 2 || 3 > 2"));
+    assert!(!truth("false || false"));
+    // `&&` binds tighter than `||`.
+    assert!(truth("true || false && false"));
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    assert!(!truth("false && (1 / 0 == 0)"));
+    assert!(truth("true || (1 / 0 == 0)"));
+    assert!(truth("true || undefined"));
+    assert!(matches!(
+        evaluate("true && (1 / 0 == 0)"),
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn not() {
+    assert!(!truth("!(1 == 1)"));
+    assert!(truth("!false"));
+    assert!(truth("!!true"));
+    assert!(truth("!(1 > 2) && 5! == 120"));
+}
+
+#[test]
+fn logical_operators_require_bools() {
+    for input in ["1 && true", "false || 1", "true && 0", "!1", "!2.5"] {
+        assert!(
+            matches!(
+                evaluate(input),
+                Err(CalcError::Eval(EvalError {
+                    kind: EvalErrorKind::TypeMismatch {
+                        expected: "bool",
+                        ..
+                    },
+                    ..
+                }))
+            ),
+            "{:?} should be a type mismatch",
+            input
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use pest::Parser;
+use pest_calculator::{
+    evaluate, parse_expr, CalcError, CalculatorParser, Environment, EvalError, EvalErrorKind, Rule,
+    Value,
+};
+
+#[test]
+fn takes_either_branch() {
+    assert_eq!(evaluate("2 > 0 ? 1 : -1").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("-2 > 0 ? 1 : -1").unwrap(), Value::Int(-1));
+}
+
+#[test]
+fn only_evaluates_the_taken_branch() {
+    assert_eq!(evaluate("true ? 1 : 1 / 0").unwrap(), Value::Int(1));
+    assert_eq!(
+        evaluate("false ? undefined : 2.5").unwrap(),
+        Value::Float(2.5)
+    );
+}
+
+#[test]
+fn requires_a_boolean_condition() {
+    match evaluate("1 ? 2 : 3") {
+        Err(CalcError::Eval(EvalError {
+            kind:
+                EvalErrorKind::TypeMismatch {
+                    expected: "bool",
+                    found: "int",
+                },
+            span,
+        })) => assert_eq!((span.start, span.end), (0, 1)),
+        other => panic!("expected a type mismatch, found {:?}", other),
+    }
+}
+
+#[test]
+fn nests_to_the_right() {
+    let sign = |x: &str| evaluate(&format!("{0} > 0 ? 1 : {0} < 0 ? -1 : 0", x)).unwrap();
+    assert_eq!(sign("5"), Value::Int(1));
+    assert_eq!(sign("-5"), Value::Int(-1));
+    assert_eq!(sign("0"), Value::Int(0));
+    assert_eq!(evaluate("true ? false ? 1 : 2 : 3").unwrap(), Value::Int(2));
+}
+
+#[test]
+fn binds_looser_than_logical_operators_and_tighter_than_assignment() {
+    assert_eq!(
+        evaluate("1 < 2 && 2 < 3 ? 1 + 1 : 0").unwrap(),
+        Value::Int(2)
+    );
+    let mut env = Environment::new();
+    let pairs = CalculatorParser::parse(Rule::statement, "x = 1 > 2 ? 10 : 20").unwrap();
+    let expr = parse_expr(pairs).unwrap();
+    assert_eq!(env.eval(&expr).unwrap(), Value::Int(20));
+    assert_eq!(env.get("x"), Some(Value::Int(20)));
+}
+
+#[test]
+fn displays_with_minimal_parentheses() {
+    for (input, expected) in [
+        ("a ? 1 : b ? 2 : 3", "a ? 1 : b ? 2 : 3"),
+        ("a ? (b ? 1 : 2) : 3", "a ? (b ? 1 : 2) : 3"),
+        ("(a ? 1 : 2) + 1", "(a ? 1 : 2) + 1"),
+        ("x = a || b ? 1 : 2", "x = a || b ? 1 : 2"),
+    ] {
+        let pairs = CalculatorParser::parse(Rule::statement, input).unwrap();
+        assert_eq!(parse_expr(pairs).unwrap().to_string(), expected);
+    }
+}
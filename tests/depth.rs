@@ -0,0 +1,101 @@
+use pest::Parser;
+use pest_calculator::{
+    evaluate, CalcError, CalculatorParser, ParseConfig, Rule, Value, DEFAULT_MAX_DEPTH,
+};
+
+fn nested(depth: usize) -> String {
+    format!("{}1{}", "(".repeat(depth), ")".repeat(depth))
+}
+
+#[test]
+fn very_deep_nesting_is_a_clean_error() {
+    match evaluate(&nested(100_000)) {
+        Err(CalcError::TooDeep(max_depth)) => assert_eq!(max_depth, DEFAULT_MAX_DEPTH),
+        other => panic!("expected a nesting error, found {:?}", other),
+    }
+    let prefix_operators = format!("{}1", "-".repeat(100_000));
+    assert!(matches!(
+        evaluate(&prefix_operators),
+        Err(CalcError::TooDeep(_))
+    ));
+}
+
+#[test]
+fn negated_exponents_nest() {
+    // Each unary minus extends over the rest of the power chain, so pest nests once per `-`.
+    for input in [
+        format!("2{}", " ^ -2".repeat(5_000)),
+        format!("2{}", " ^ -(2)".repeat(5_000)),
+        format!("(2{})", " ^ -2".repeat(5_000)),
+    ] {
+        assert!(
+            matches!(evaluate(&input), Err(CalcError::TooDeep(_))),
+            "{}...",
+            &input[..20]
+        );
+    }
+    assert_eq!(
+        evaluate("2 ^ -2 ^ -2").unwrap(),
+        evaluate("2 ^ -(2 ^ -2)").unwrap()
+    );
+    // The chain ends at any other operator, so this does not nest.
+    let sum = vec!["-2 ^ 2"; 60].join(" + ");
+    assert_eq!(evaluate(&sum).unwrap(), Value::Int(-240));
+}
+
+#[test]
+fn nesting_up_to_the_limit_is_allowed() {
+    assert_eq!(evaluate(&nested(DEFAULT_MAX_DEPTH)).unwrap(), Value::Int(1));
+    assert!(matches!(
+        evaluate(&nested(DEFAULT_MAX_DEPTH + 1)),
+        Err(CalcError::TooDeep(_))
+    ));
+    // Long expressions that do not nest are not limited.
+    let sum = vec!["-1"; 2 * DEFAULT_MAX_DEPTH].join(" - ");
+    assert_eq!(
+        evaluate(&sum).unwrap(),
+        Value::Int(2 * DEFAULT_MAX_DEPTH as i64 - 2)
+    );
+}
+
+#[test]
+fn limit_is_configurable() {
+    let config = ParseConfig {
+        max_depth: 3,
+        ..ParseConfig::default()
+    };
+    for (input, ok) in [
+        ("((1))", true),
+        ("(((1)))", true),
+        ("((((1))))", false),
+        ("-(-(1))", false),
+        ("max(1, (2))", true),
+        ("max(1, ((2)))", true),
+        ("max(1, (((2))))", false),
+        ("a = b = c = 1", true),
+        ("a = b = c = d = 1", false),
+        ("2 ^ -2", true),
+        ("-2 ^ 2 + 1", true),
+        ("2 ^ -2 ^ -2 ^ -2", true),
+        ("2 ^ -2 ^ -2 ^ -2 ^ -2", false),
+    ] {
+        let result = config
+            .parse(Rule::statement, input)
+            .and_then(|pairs| config.parse_expr(pairs));
+        assert_eq!(result.is_ok(), ok, "{:?} gave {:?}", input, result);
+    }
+}
+
+#[test]
+fn depth_is_tracked_while_building_the_tree() {
+    // Parsed without the check that `ParseConfig::parse` does up front.
+    let pairs = CalculatorParser::parse(Rule::equation, "1 + (2 * (3 - (4)))").unwrap();
+    assert!(matches!(
+        ParseConfig {
+            max_depth: 2,
+            ..ParseConfig::default()
+        }
+        .parse_expr(pairs),
+        Err(CalcError::TooDeep(2))
+    ));
+}
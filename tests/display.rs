@@ -0,0 +1,143 @@
+use pest_calculator::{evaluate, Expr, ExprKind, Int, Op, Radix, Value};
+
+fn node(kind: ExprKind) -> Box<Expr> {
+    Box::new(kind.into())
+}
+
+fn int(i: Int) -> Box<Expr> {
+    node(ExprKind::Integer(i, Radix::Decimal))
+}
+
+fn bin(lhs: Box<Expr>, op: Op, rhs: Box<Expr>) -> Box<Expr> {
+    node(ExprKind::BinOp { lhs, op, rhs })
+}
+
+#[test]
+fn omits_parentheses_implied_by_precedence() {
+    let expr = bin(int(1), Op::Add, bin(int(2), Op::Multiply, int(3)));
+    assert_eq!(expr.to_string(), "1 + 2 * 3");
+}
+
+#[test]
+fn keeps_parentheses_that_override_precedence() {
+    let expr = bin(bin(int(1), Op::Add, int(2)), Op::Multiply, int(3));
+    assert_eq!(expr.to_string(), "(1 + 2) * 3");
+}
+
+#[test]
+fn respects_associativity() {
+    let left = bin(bin(int(1), Op::Subtract, int(2)), Op::Subtract, int(3));
+    assert_eq!(left.to_string(), "1 - 2 - 3");
+    let right = bin(int(1), Op::Subtract, bin(int(2), Op::Subtract, int(3)));
+    assert_eq!(right.to_string(), "1 - (2 - 3)");
+
+    let right = bin(int(2), Op::Power, bin(int(3), Op::Power, int(2)));
+    assert_eq!(right.to_string(), "2 ^ 3 ^ 2");
+    let left = bin(bin(int(2), Op::Power, int(3)), Op::Power, int(2));
+    assert_eq!(left.to_string(), "(2 ^ 3) ^ 2");
+}
+
+#[test]
+fn renders_unary_and_postfix_operators() {
+    let expr = node(ExprKind::UnaryMinus(bin(int(5), Op::Subtract, int(2))));
+    assert_eq!(expr.to_string(), "-(5 - 2)");
+    let expr = node(ExprKind::Factorial(node(ExprKind::UnaryMinus(int(3)))));
+    assert_eq!(expr.to_string(), "(-3)!");
+    let expr = bin(
+        node(ExprKind::Float(1.5)),
+        Op::Modulo,
+        node(ExprKind::Factorial(int(3))),
+    );
+    assert_eq!(expr.to_string(), "1.5 % 3!");
+}
+
+#[test]
+fn parenthesizes_negations_only_on_the_left_of_power() {
+    let neg = |operand| node(ExprKind::UnaryMinus(operand));
+    let expr = neg(bin(int(2), Op::Power, int(2)));
+    assert_eq!(expr.to_string(), "-2 ^ 2");
+    let expr = bin(neg(int(2)), Op::Power, int(2));
+    assert_eq!(expr.to_string(), "(-2) ^ 2");
+    let expr = bin(int(2), Op::Power, neg(int(1)));
+    assert_eq!(expr.to_string(), "2 ^ -1");
+    let expr = bin(neg(int(2)), Op::Multiply, int(3));
+    assert_eq!(expr.to_string(), "-2 * 3");
+
+    // Negative literals, e.g. from constant folding, read the same as a negation.
+    let expr = bin(int(-2), Op::Power, int(2));
+    assert_eq!(expr.to_string(), "(-2) ^ 2");
+    let expr = node(ExprKind::Factorial(int(-3)));
+    assert_eq!(expr.to_string(), "(-3)!");
+}
+
+#[test]
+fn renders_function_calls() {
+    let expr = node(ExprKind::Call {
+        name: "max".to_owned(),
+        args: vec![*bin(int(1), Op::Add, int(2)), *int(3)],
+    });
+    assert_eq!(expr.to_string(), "max(1 + 2, 3)");
+}
+
+#[test]
+fn renders_comparisons() {
+    let expr = bin(bin(int(1), Op::Add, int(2)), Op::LessEqual, int(3));
+    assert_eq!(expr.to_string(), "1 + 2 <= 3");
+    let expr = bin(int(1), Op::Equal, bin(int(2), Op::Less, int(3)));
+    assert_eq!(expr.to_string(), "1 == 2 < 3");
+    let expr = bin(bin(int(1), Op::Equal, int(2)), Op::Less, int(3));
+    assert_eq!(expr.to_string(), "(1 == 2) < 3");
+}
+
+#[test]
+fn renders_logical_operators() {
+    let comparison = bin(int(1), Op::Less, int(2));
+    let expr = bin(
+        node(ExprKind::Not(comparison)),
+        Op::Or,
+        bin(
+            node(ExprKind::Bool(true)),
+            Op::And,
+            node(ExprKind::Bool(false)),
+        ),
+    );
+    assert_eq!(expr.to_string(), "!(1 < 2) || true && false");
+    let expr = bin(
+        bin(
+            node(ExprKind::Bool(true)),
+            Op::Or,
+            node(ExprKind::Bool(false)),
+        ),
+        Op::And,
+        node(ExprKind::Bool(false)),
+    );
+    assert_eq!(expr.to_string(), "(true || false) && false");
+}
+
+#[test]
+fn writes_values_that_no_literal_can_write() {
+    let min = int(Int::MIN);
+    assert_eq!(min.to_string(), "-9223372036854775807 - 1");
+    assert_eq!(evaluate(&min.to_string()).unwrap(), Value::Int(Int::MIN));
+    assert_eq!(
+        bin(min, Op::Power, int(2)).to_string(),
+        "(-9223372036854775807 - 1) ^ 2"
+    );
+    let hex = node(ExprKind::Integer(Int::MIN, Radix::Hexadecimal));
+    assert_eq!(hex.to_string(), "-0x7fffffffffffffff - 1");
+
+    let float = |x: f64| node(ExprKind::Float(x)).to_string();
+    assert_eq!(float(f64::INFINITY), "1e999");
+    assert_eq!(evaluate("1e999").unwrap(), Value::Float(f64::INFINITY));
+    assert_eq!(
+        bin(
+            int(2),
+            Op::Multiply,
+            node(ExprKind::Float(f64::NEG_INFINITY))
+        )
+        .to_string(),
+        "2 * -1e999"
+    );
+    assert_eq!(float(f64::NAN), "1e999 - 1e999");
+    assert!(matches!(evaluate(&float(f64::NAN)).unwrap(), Value::Float(x) if x.is_nan()));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{Environment, EvalConfig, EvalError, EvalErrorKind, Value};
+
+fn eval_with(strict_integer_division: bool, input: &str) -> Result<Value, EvalError> {
+    let expr = parse(input);
+    Environment::with_config(EvalConfig {
+        strict_integer_division,
+        ..EvalConfig::default()
+    })
+    .eval(&expr)
+}
+
+#[test]
+fn truncating_is_the_default() {
+    let expr = parse("7 / 2");
+    assert_eq!(Environment::new().eval(&expr).unwrap(), Value::Int(3));
+    assert_eq!(eval_with(false, "-7 / 2").unwrap(), Value::Int(-3));
+}
+
+#[test]
+fn strict_division_allows_exact_quotients() {
+    assert_eq!(eval_with(true, "6 / 3").unwrap(), Value::Int(2));
+    assert_eq!(eval_with(true, "-6 / 3").unwrap(), Value::Int(-2));
+    assert_eq!(eval_with(true, "0 / 5").unwrap(), Value::Int(0));
+}
+
+#[test]
+fn strict_division_rejects_remainders() {
+    let error = eval_with(true, "1 + 7 / 2").unwrap_err();
+    assert!(matches!(error.kind, EvalErrorKind::InexactDivision));
+    assert_eq!((error.span.start, error.span.end), (4, 9));
+    assert!(matches!(
+        eval_with(true, "-7 / 2").unwrap_err().kind,
+        EvalErrorKind::InexactDivision
+    ));
+}
+
+#[test]
+fn strict_division_leaves_other_cases_alone() {
+    assert_eq!(eval_with(true, "7.0 / 2").unwrap(), Value::Float(3.5));
+    assert_eq!(eval_with(true, "7 % 2").unwrap(), Value::Int(1));
+    assert!(matches!(
+        eval_with(true, "7 / 0").unwrap_err().kind,
+        EvalErrorKind::DivisionByZero
+    ));
+    assert!(matches!(
+        eval_with(true, "(-9223372036854775807 - 1) / -1")
+            .unwrap_err()
+            .kind,
+        EvalErrorKind::Overflow("division")
+    ));
+}
+
+fn eval(input: &str) -> Value {
+    eval_with(false, input).unwrap()
+}
+
+#[test]
+fn floor_division_rounds_down() {
+    assert_eq!(eval("7 // 2"), Value::Int(3));
+    assert_eq!(eval("-7 // 2"), Value::Int(-4));
+    assert_eq!(eval("7 // -2"), Value::Int(-4));
+    assert_eq!(eval("-7 // -2"), Value::Int(3));
+    assert_eq!(eval("-6 // 2"), Value::Int(-3));
+    assert_eq!(eval("-7.5 // 2"), Value::Float(-4.0));
+    // `/` rounds towards zero instead.
+    assert_eq!(eval("7 / 2"), Value::Int(3));
+    assert_eq!(eval("-7 / 2"), Value::Int(-3));
+}
+
+#[test]
+fn floor_division_binds_like_division() {
+    assert_eq!(eval("1 + 7 // 2 * 2"), Value::Int(7));
+    assert_eq!(eval("100 // 7 // 2"), Value::Int(7));
+    assert!(matches!(
+        eval_with(false, "1 // 0").unwrap_err().kind,
+        EvalErrorKind::DivisionByZero
+    ));
+    assert!(matches!(
+        eval_with(false, "(-9223372036854775807 - 1) // -1")
+            .unwrap_err()
+            .kind,
+        EvalErrorKind::Overflow("floor division")
+    ));
+}
+
+#[test]
+fn float_division_makes_slash_divide_in_floating_point() {
+    let float_division = |input| {
+        let expr = parse(input);
+        Environment::with_config(EvalConfig {
+            float_division: true,
+            ..EvalConfig::default()
+        })
+        .eval(&expr)
+        .unwrap()
+    };
+    assert_eq!(float_division("7 / 2"), Value::Float(3.5));
+    assert_eq!(float_division("6 / 3"), Value::Float(2.0));
+    assert_eq!(float_division("-7 / 2"), Value::Float(-3.5));
+    assert_eq!(float_division("7 // 2"), Value::Int(3));
+    assert_eq!(float_division("-7 // 2"), Value::Int(-4));
+}
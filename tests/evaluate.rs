@@ -0,0 +1,111 @@
+use pest::error::ErrorVariant;
+use pest_calculator::{
+    evaluate, evaluate_to_string, CalcError, EvalError, EvalErrorKind, Span, Value,
+};
+
+#[test]
+fn evaluates_valid_expression() {
+    assert_eq!(evaluate("2 + 3 * 4").unwrap(), Value::Int(14));
+}
+
+#[test]
+fn reports_syntax_error() {
+    assert!(matches!(evaluate("2 +"), Err(CalcError::Parse(_))));
+}
+
+#[test]
+fn syntax_errors_are_readable() {
+    assert_eq!(
+        evaluate("1 + ").unwrap_err().to_string(),
+        [
+            "parse error",
+            " --> 1:5",
+            "  |",
+            "1 | 1 + ",
+            "  |     ^---",
+            "  |",
+            "  = expression ends with an operator",
+        ]
+        .join("\n")
+    );
+    assert!(evaluate("1 + )")
+        .unwrap_err()
+        .to_string()
+        .ends_with("expected operand"));
+    assert!(evaluate("1 2")
+        .unwrap_err()
+        .to_string()
+        .ends_with("expected end of input, `!`, `%`, or operator"));
+}
+
+#[test]
+fn reports_evaluation_error() {
+    assert!(matches!(
+        evaluate("1 / (2 - 2)"),
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn evaluation_error_points_at_the_failing_subexpression() {
+    let input = "1 + (6 / (2 - 2))";
+    match evaluate(input) {
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            span,
+        })) => {
+            assert_eq!(span, Span::new(4, 17));
+            assert_eq!(&input[span.start..span.end], "(6 / (2 - 2))");
+        }
+        other => panic!("expected a division by zero, found {:?}", other),
+    }
+}
+
+#[test]
+fn integers_are_64_bit() {
+    assert_eq!(
+        evaluate("1000000 * 1000000").unwrap(),
+        Value::Int(1_000_000_000_000)
+    );
+    assert_eq!(evaluate("2147483647 + 1").unwrap(), Value::Int(2147483648));
+    assert_eq!(evaluate("0xFFFFFFFF + 1").unwrap(), Value::Int(1 << 32));
+    assert_eq!(evaluate("20!").unwrap(), Value::Int(2432902008176640000));
+}
+
+#[test]
+fn evaluate_to_string_formats_the_result() {
+    assert_eq!(evaluate_to_string("1 + 2 * 3"), "7");
+    assert_eq!(evaluate_to_string("7 / 2.0"), "3.5");
+}
+
+#[test]
+fn evaluate_to_string_formats_errors() {
+    assert_eq!(evaluate_to_string("1 / 0"), "Error: division by zero");
+    assert!(evaluate_to_string("1 +").starts_with("Error: parse error\n"));
+}
+
+#[test]
+fn misplaced_operators_are_named() {
+    let message = |input| match evaluate(input) {
+        Err(CalcError::Parse(e)) => match e.variant {
+            ErrorVariant::CustomError { message } => message,
+            variant => panic!("{} should name the operator, got {:?}", input, variant),
+        },
+        other => panic!("{} should fail to parse, got {:?}", input, other),
+    };
+    assert_eq!(message("+5"), "expression starts with a binary operator");
+    assert_eq!(message("(* 2)"), "expression starts with a binary operator");
+    assert_eq!(message("5+"), "expression ends with an operator");
+    assert_eq!(
+        message("5 - # nothing follows"),
+        "expression ends with an operator"
+    );
+    assert_eq!(message("5 * * 3"), "two operators in a row");
+    assert_eq!(message("2 +<= 3"), "two operators in a row");
+    // An operand may start with `-` or `!`, so these are not misplaced operators.
+    assert_eq!(evaluate("5 * -3").unwrap(), Value::Int(-15));
+    assert_eq!(evaluate("!true == false").unwrap(), Value::Bool(true));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{
+    fold_constants, EnabledOperators, Environment, EvalConfig, Expr, ExprKind, ModuloMode,
+};
+
+fn fold(input: &str) -> Expr {
+    fold_constants(parse(input))
+}
+
+#[test]
+fn constant_tree_collapses_to_a_leaf() {
+    assert!(matches!(fold("2 + 3 * 4").kind, ExprKind::Integer(14, _)));
+    assert!(matches!(fold("-(5 - 2)!").kind, ExprKind::Integer(-6, _)));
+    assert!(matches!(fold("max(1, 2.5) * 2").kind, ExprKind::Float(f) if f == 5.0));
+    assert!(matches!(fold("1 < 2 && !false").kind, ExprKind::Bool(true)));
+    assert!(matches!(fold("pi").kind, ExprKind::Float(f) if f == std::f64::consts::PI));
+}
+
+#[test]
+fn mixed_tree_folds_partially() {
+    assert_eq!(fold("2 * 3 + x").to_string(), "6 + x");
+    assert_eq!(
+        fold("x * (1 + 1) - abs(y - 2 ^ 3)").to_string(),
+        "x * 2 - abs(y - 8)"
+    );
+    // No reassociation takes place.
+    assert_eq!(fold("x + 2 + 3").to_string(), "x + 2 + 3");
+}
+
+#[test]
+fn constant_conditions_select_a_branch() {
+    assert_eq!(fold("1 < 2 ? x * (2 + 2) : 1 / 0").to_string(), "x * 4");
+    assert_eq!(fold("c ? 1 + 1 : y").to_string(), "c ? 2 : y");
+}
+
+#[test]
+fn failing_subtrees_are_left_unfolded() {
+    assert_eq!(fold("1 / 0 + 2 * 2").to_string(), "1 / 0 + 4");
+    assert_eq!(
+        fold("9223372036854775807 + 1").to_string(),
+        "9223372036854775807 + 1"
+    );
+    assert_eq!(fold("sqrt(0 - 1)").to_string(), "sqrt(-1)");
+    assert_eq!(fold("foo(1 + 1)").to_string(), "foo(2)");
+}
+
+#[test]
+fn results_without_a_literal_are_printed_so_they_parse_again() {
+    assert_eq!(
+        fold("x * (-2) ^ 63").to_string(),
+        "x * (-9223372036854775807 - 1)"
+    );
+    assert_eq!(fold("x + 1e400").to_string(), "x + 1e999");
+    assert_eq!(
+        fold("x + (1e400 - 1e400)").to_string(),
+        "x + (1e999 - 1e999)"
+    );
+}
+
+#[test]
+fn folding_follows_the_configuration_of_the_environment() {
+    let fold_with = |config: EvalConfig, input: &str| {
+        Environment::with_config(config)
+            .fold_constants(parse(input))
+            .to_string()
+    };
+    let euclidean = EvalConfig {
+        modulo: ModuloMode::Euclidean,
+        ..EvalConfig::default()
+    };
+    assert_eq!(fold_with(euclidean, "-7 % 3 + x"), "2 + x");
+    assert_eq!(fold("-7 % 3 + x").to_string(), "-1 + x");
+
+    let strict = EvalConfig {
+        strict_integer_division: true,
+        ..EvalConfig::default()
+    };
+    assert_eq!(fold_with(strict, "7 / 2 + 8 / 2"), "7 / 2 + 4");
+
+    let float_division = EvalConfig {
+        float_division: true,
+        ..EvalConfig::default()
+    };
+    assert_eq!(fold_with(float_division, "7 / 2 * x"), "3.5 * x");
+
+    let no_power = EvalConfig {
+        operators: EnabledOperators {
+            power: false,
+            ..EnabledOperators::default()
+        },
+        ..EvalConfig::default()
+    };
+    assert_eq!(fold_with(no_power, "2 ^ 3 + 1 * 2"), "2 ^ 3 + 2");
+}
@@ -0,0 +1,117 @@
+use pest_calculator::{format_grouped, format_result, Radix, Value};
+
+#[test]
+fn formats_integers_in_decimal() {
+    assert_eq!(
+        format_result(&Value::Int(255), Radix::Decimal).unwrap(),
+        "255"
+    );
+    assert_eq!(
+        format_result(&Value::Int(-42), Radix::Decimal).unwrap(),
+        "-42"
+    );
+}
+
+#[test]
+fn formats_integers_in_hexadecimal() {
+    assert_eq!(
+        format_result(&Value::Int(255), Radix::Hexadecimal).unwrap(),
+        "0xff"
+    );
+    assert_eq!(
+        format_result(&Value::Int(0), Radix::Hexadecimal).unwrap(),
+        "0x0"
+    );
+    assert_eq!(
+        format_result(&Value::Int(-255), Radix::Hexadecimal).unwrap(),
+        "-0xff"
+    );
+    assert_eq!(
+        format_result(&Value::Int(i64::MIN), Radix::Hexadecimal).unwrap(),
+        "-0x8000000000000000"
+    );
+}
+
+#[test]
+fn formats_integers_in_binary_and_octal() {
+    assert_eq!(
+        format_result(&Value::Int(5), Radix::Binary).unwrap(),
+        "0b101"
+    );
+    assert_eq!(
+        format_result(&Value::Int(-5), Radix::Binary).unwrap(),
+        "-0b101"
+    );
+    assert_eq!(format_result(&Value::Int(8), Radix::Octal).unwrap(), "0o10");
+}
+
+#[test]
+fn formats_large_floats_in_scientific_notation() {
+    assert_eq!(
+        format_result(&Value::Float(1.5e20), Radix::Decimal).unwrap(),
+        "1.5e20"
+    );
+    assert_eq!(
+        format_result(&Value::Float(-1e15), Radix::Decimal).unwrap(),
+        "-1e15"
+    );
+    assert_eq!(
+        format_result(&Value::Float(2.5), Radix::Decimal).unwrap(),
+        "2.5"
+    );
+    assert_eq!(
+        format_result(&Value::Float(1000.0), Radix::Decimal).unwrap(),
+        "1000.0"
+    );
+}
+
+#[test]
+fn rejects_floats_in_other_radixes() {
+    assert_eq!(
+        format_result(&Value::Float(2.5), Radix::Hexadecimal).unwrap_err(),
+        "cannot print the float 2.5 in hexadecimal"
+    );
+}
+
+#[test]
+fn formats_booleans_in_any_radix() {
+    assert_eq!(
+        format_result(&Value::Bool(true), Radix::Binary).unwrap(),
+        "true"
+    );
+}
+
+#[test]
+fn groups_integers_by_thousands() {
+    assert_eq!(format_grouped(&Value::Int(1_000_000), ','), "1,000,000");
+    assert_eq!(format_grouped(&Value::Int(123_456), ','), "123,456");
+    assert_eq!(format_grouped(&Value::Int(12_345), '_'), "12_345");
+    assert_eq!(format_grouped(&Value::Int(999), ','), "999");
+    assert_eq!(format_grouped(&Value::Int(0), ','), "0");
+}
+
+#[test]
+fn groups_negative_integers_after_the_sign() {
+    assert_eq!(format_grouped(&Value::Int(-1_234), ','), "-1,234");
+    assert_eq!(format_grouped(&Value::Int(-123), ','), "-123");
+    assert_eq!(
+        format_grouped(&Value::Int(i64::MIN), ','),
+        "-9,223,372,036,854,775,808"
+    );
+}
+
+#[test]
+fn groups_only_the_integer_part_of_floats() {
+    assert_eq!(
+        format_grouped(&Value::Float(1234567.891), ','),
+        "1,234,567.891"
+    );
+    assert_eq!(
+        format_grouped(&Value::Float(-1234.5678), '_'),
+        "-1_234.5678"
+    );
+    assert_eq!(format_grouped(&Value::Float(0.123456), ','), "0.123456");
+    assert_eq!(format_grouped(&Value::Float(1.5e20), ','), "1.5e20");
+    assert_eq!(format_grouped(&Value::Float(f64::INFINITY), ','), "inf");
+    assert_eq!(format_grouped(&Value::Bool(true), ','), "true");
+}
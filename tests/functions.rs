@@ -0,0 +1,177 @@
+use pest_calculator::{evaluate, CalcError, EvalErrorKind, Value};
+
+fn eval_error(input: &str) -> EvalErrorKind {
+    match evaluate(input) {
+        Err(CalcError::Eval(e)) => e.kind,
+        other => panic!(
+            "expected an evaluation error for {:?}, found {:?}",
+            input, other
+        ),
+    }
+}
+
+#[test]
+fn abs() {
+    assert_eq!(evaluate("abs(-3)").unwrap(), Value::Int(3));
+    assert_eq!(evaluate("abs(2.5 - 4)").unwrap(), Value::Float(1.5));
+}
+
+#[test]
+fn sqrt() {
+    assert_eq!(evaluate("sqrt(16)").unwrap(), Value::Float(4.0));
+    assert_eq!(evaluate("sqrt(2)").unwrap(), Value::Float(2f64.sqrt()));
+}
+
+#[test]
+fn min_and_max() {
+    assert_eq!(evaluate("max(1, 5)").unwrap(), Value::Int(5));
+    assert_eq!(evaluate("min(1, 5)").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("min(2, 1.5)").unwrap(), Value::Float(1.5));
+    assert_eq!(evaluate("max(abs(-7), 2 * 3)").unwrap(), Value::Int(7));
+}
+
+#[test]
+fn min_and_max_take_any_number_of_arguments() {
+    assert_eq!(evaluate("max(1,7,3,2)").unwrap(), Value::Int(7));
+    assert_eq!(evaluate("min(4, -1, 3, 2)").unwrap(), Value::Int(-1));
+    assert_eq!(evaluate("max(5)").unwrap(), Value::Int(5));
+    assert_eq!(evaluate("max(1, 2.5, 2)").unwrap(), Value::Float(2.5));
+    assert_eq!(evaluate("min(1, 2.5, 3)").unwrap(), Value::Float(1.0));
+    assert!(matches!(
+        eval_error("max(1, true, 3)"),
+        EvalErrorKind::TypeMismatch { .. }
+    ));
+    assert!(matches!(
+        eval_error("min(true)"),
+        EvalErrorKind::TypeMismatch { .. }
+    ));
+
+    let kind = eval_error("min()");
+    assert!(matches!(
+        kind,
+        EvalErrorKind::ArityMismatch {
+            expected: 1,
+            variadic: true,
+            found: 0,
+            ..
+        }
+    ));
+    assert_eq!(
+        kind.to_string(),
+        "min expects at least 1 argument(s) but got 0"
+    );
+}
+
+#[test]
+fn pow() {
+    assert_eq!(evaluate("pow(2, 10)").unwrap(), Value::Int(1024));
+    assert_eq!(evaluate("pow(2, -1)").unwrap(), Value::Float(0.5));
+}
+
+#[test]
+fn percent() {
+    assert_eq!(evaluate("percent(1, 4)").unwrap(), Value::Float(25.0));
+    assert_eq!(evaluate("percent(30, 20)").unwrap(), Value::Float(150.0));
+    assert_eq!(evaluate("percent(-1.5, 6)").unwrap(), Value::Float(-25.0));
+    assert!(matches!(
+        eval_error("percent(1, 0)"),
+        EvalErrorKind::DivisionByZero
+    ));
+}
+
+#[test]
+fn diff() {
+    assert_eq!(evaluate("diff(3, 10)").unwrap(), Value::Int(7));
+    assert_eq!(evaluate("diff(10, 3)").unwrap(), Value::Int(7));
+    assert_eq!(evaluate("diff(-2, 0.5)").unwrap(), Value::Float(2.5));
+    assert!(matches!(
+        eval_error("diff(true, 1)"),
+        EvalErrorKind::TypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn clamp() {
+    assert_eq!(evaluate("clamp(5, 1, 10)").unwrap(), Value::Int(5));
+    assert_eq!(evaluate("clamp(-5, 1, 10)").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("clamp(50, 1, 10)").unwrap(), Value::Int(10));
+    assert_eq!(evaluate("clamp(3, 3, 3)").unwrap(), Value::Int(3));
+    assert_eq!(evaluate("clamp(0.5, 0, 1)").unwrap(), Value::Float(0.5));
+    assert_eq!(evaluate("clamp(7, 0, 2.5)").unwrap(), Value::Float(2.5));
+    assert!(matches!(
+        eval_error("clamp(1, 2)"),
+        EvalErrorKind::ArityMismatch {
+            expected: 3,
+            found: 2,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn clamp_rejects_bounds_in_the_wrong_order() {
+    let kind = eval_error("clamp(5, 10, 1)");
+    assert!(matches!(
+        kind,
+        EvalErrorKind::InvalidArguments { ref function, .. } if function == "clamp"
+    ));
+    assert_eq!(
+        kind.to_string(),
+        "invalid arguments to clamp: the lower bound is greater than the upper bound"
+    );
+    assert!(matches!(
+        eval_error("clamp(5, 1.5, 1)"),
+        EvalErrorKind::InvalidArguments { .. }
+    ));
+}
+
+#[test]
+fn unknown_function() {
+    assert!(matches!(eval_error("foo(1)"), EvalErrorKind::UnknownFunction(name) if name == "foo"));
+}
+
+#[test]
+fn wrong_number_of_arguments() {
+    assert!(matches!(
+        eval_error("abs(1, 2)"),
+        EvalErrorKind::ArityMismatch {
+            expected: 1,
+            found: 2,
+            ..
+        }
+    ));
+    assert!(matches!(
+        eval_error("pow(1)"),
+        EvalErrorKind::ArityMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        }
+    ));
+    let kind = eval_error("sqrt(4,9)");
+    assert!(matches!(
+        kind,
+        EvalErrorKind::ArityMismatch {
+            expected: 1,
+            variadic: false,
+            found: 2,
+            ..
+        }
+    ));
+    assert_eq!(kind.to_string(), "sqrt expects 1 argument(s) but got 2");
+}
+
+#[test]
+fn function_names_ignore_case() {
+    assert_eq!(evaluate("SQRT(4)").unwrap(), Value::Float(2.0));
+    assert_eq!(evaluate("MAX(1, 2)").unwrap(), Value::Int(2));
+    assert_eq!(evaluate("Abs(-1)").unwrap(), Value::Int(1));
+    assert!(matches!(
+        eval_error("MAX()"),
+        EvalErrorKind::ArityMismatch { function, .. } if function == "max"
+    ));
+    assert!(matches!(
+        eval_error("Maxi(1)"),
+        EvalErrorKind::UnknownFunction(name) if name == "Maxi"
+    ));
+}
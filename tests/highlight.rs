@@ -0,0 +1,69 @@
+use pest_calculator::{highlight, ParseConfig};
+
+const NUMBER: &str = "\x1b[36m";
+const OPERATOR: &str = "\x1b[33m";
+const PARENTHESIS: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+fn paint(color: &str, text: &str) -> String {
+    format!("{}{}{}", color, text, RESET)
+}
+
+#[test]
+fn colors_numbers_operators_and_parentheses() {
+    assert_eq!(
+        highlight("1 + 0xff*2.5"),
+        format!(
+            "{} {} {}{}{}",
+            paint(NUMBER, "1"),
+            paint(OPERATOR, "+"),
+            paint(NUMBER, "0xff"),
+            paint(OPERATOR, "*"),
+            paint(NUMBER, "2.5")
+        )
+    );
+    assert_eq!(
+        highlight("-(x)!"),
+        format!(
+            "{}{}x{}{}",
+            paint(OPERATOR, "-"),
+            paint(PARENTHESIS, "("),
+            paint(PARENTHESIS, ")"),
+            paint(OPERATOR, "!")
+        )
+    );
+    assert_eq!(
+        highlight("max(a, 1) >= 1"),
+        format!(
+            "max{}a, {}{} {} {}",
+            paint(PARENTHESIS, "("),
+            paint(NUMBER, "1"),
+            paint(PARENTHESIS, ")"),
+            paint(OPERATOR, ">="),
+            paint(NUMBER, "1")
+        )
+    );
+}
+
+#[test]
+fn leaves_names_and_comments_alone() {
+    assert_eq!(
+        highlight("x = y /* (-1) */ # 2 * (3)"),
+        format!("x {} y /* (-1) */ # 2 * (3)", paint(OPERATOR, "="))
+    );
+    assert_eq!(
+        highlight("let t = true in t"),
+        format!("let t {} true in t", paint(OPERATOR, "="))
+    );
+}
+
+#[test]
+fn returns_input_that_does_not_parse_unchanged() {
+    assert_eq!(highlight("1 +"), "1 +");
+    assert_eq!(highlight("2x"), "2x");
+    let config = ParseConfig {
+        implicit_multiplication: true,
+        ..ParseConfig::default()
+    };
+    assert_eq!(config.highlight("2x"), format!("{}x", paint(NUMBER, "2")));
+}
@@ -0,0 +1,81 @@
+use pest_calculator::{CalcError, Environment, Expr, ParseConfig, Rule, Value};
+
+const IMPLICIT: ParseConfig = ParseConfig {
+    max_depth: pest_calculator::DEFAULT_MAX_DEPTH,
+    implicit_multiplication: true,
+};
+
+fn parse(input: &str) -> Result<Expr, CalcError> {
+    let pairs = IMPLICIT.parse(Rule::equation, input)?;
+    IMPLICIT.parse_expr(pairs)
+}
+
+fn eval(input: &str) -> Value {
+    let mut env = Environment::new();
+    env.set("x", Value::Int(5));
+    env.eval(&parse(input).unwrap()).unwrap()
+}
+
+#[test]
+fn multiplies_by_a_parenthesized_expression() {
+    assert_eq!(eval("2(3+4)"), Value::Int(14));
+    assert_eq!(eval("(1 + 1)(2 + 2)"), Value::Int(8));
+    assert_eq!(eval("2 (3) (4)"), Value::Int(24));
+}
+
+#[test]
+fn multiplies_by_a_name() {
+    assert_eq!(eval("3x"), Value::Int(15));
+    assert_eq!(eval("2 x"), Value::Int(10));
+    assert_eq!(eval("2pi"), eval("2 * pi"));
+    assert_eq!(eval("2 sqrt(4)"), Value::Float(4.0));
+}
+
+#[test]
+fn binds_like_multiplication() {
+    assert_eq!(parse("2x^2").unwrap().to_string(), "2 * x ^ 2");
+    assert_eq!(parse("1 / 2x").unwrap().to_string(), "1 / 2 * x");
+    assert_eq!(parse("1 + 2x").unwrap().to_string(), "1 + 2 * x");
+}
+
+#[test]
+fn keeps_subtraction_and_calls() {
+    assert_eq!(eval("2 -3"), Value::Int(-1));
+    assert_eq!(eval("2 - 3"), Value::Int(-1));
+    assert_eq!(parse("abs(2)").unwrap().to_string(), "abs(2)");
+    assert_eq!(eval("x2 = 3"), Value::Int(3));
+    assert_eq!(eval("2e3"), Value::Float(2000.0));
+}
+
+#[test]
+fn does_not_multiply_numbers() {
+    assert!(matches!(parse("2 3"), Err(CalcError::Parse(_))));
+}
+
+#[test]
+fn is_off_by_default() {
+    let config = ParseConfig::default();
+    assert!(matches!(
+        config.parse(Rule::equation, "2(3+4)"),
+        Err(CalcError::Parse(_))
+    ));
+    assert!(matches!(
+        config.parse(Rule::equation, "3x"),
+        Err(CalcError::Parse(_))
+    ));
+}
+
+#[test]
+fn applies_to_programs() {
+    let pairs = IMPLICIT.parse(Rule::program, "y = 2x; 3y").unwrap();
+    let statements = IMPLICIT.parse_program(pairs).unwrap();
+    let mut env = Environment::new();
+    env.set("x", Value::Int(5));
+    let results: Vec<_> = statements.iter().map(|s| env.eval(s).unwrap()).collect();
+    assert_eq!(results, [Value::Int(10), Value::Int(30)]);
+}
+
+#[test]
+fn does_not_multiply_by_keywords() {
+    assert_eq!(eval("let y = 2x in 3y"), Value::Int(30));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{parse, try_parse};
+use pest_calculator::{CalcError, Environment, EvalError, EvalErrorKind, Value};
+
+fn eval(env: &mut Environment, input: &str) -> Result<Value, EvalError> {
+    env.eval(&parse(input))
+}
+
+#[test]
+fn binds_a_name_in_the_body() {
+    let mut env = Environment::new();
+    assert_eq!(eval(&mut env, "let x = 2 in x * x").unwrap(), Value::Int(4));
+    assert_eq!(env.get("x"), None);
+}
+
+#[test]
+fn inner_bindings_shadow_outer_ones() {
+    let mut env = Environment::new();
+    env.set("x", Value::Int(100));
+    assert_eq!(
+        eval(&mut env, "let x = 1 in (let x = x + 1 in x * 10) + x").unwrap(),
+        Value::Int(21)
+    );
+    assert_eq!(
+        eval(&mut env, "let x = 2 in let y = x + 1 in x * y").unwrap(),
+        Value::Int(6)
+    );
+    assert_eq!(env.get("x"), Some(Value::Int(100)));
+    assert_eq!(env.get("y"), None);
+}
+
+#[test]
+fn bindings_are_gone_after_an_error() {
+    let mut env = Environment::new();
+    assert!(eval(&mut env, "let x = 1 in x / 0").is_err());
+    assert!(matches!(
+        eval(&mut env, "x"),
+        Err(EvalError {
+            kind: EvalErrorKind::UndefinedVariable(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn assignment_in_the_body_updates_the_binding() {
+    let mut env = Environment::new();
+    assert_eq!(
+        eval(&mut env, "let x = 1 in (x = x + 1) + (y = x)").unwrap(),
+        Value::Int(4)
+    );
+    assert_eq!(env.get("x"), None);
+    assert_eq!(env.get("y"), Some(Value::Int(2)));
+}
+
+#[test]
+fn binds_more_loosely_than_operators() {
+    assert_eq!(
+        parse("1 + (let x = 1 in x + 1) * 2").to_string(),
+        "1 + (let x = 1 in x + 1) * 2"
+    );
+    assert_eq!(
+        parse("let x = y ? 1 : 2 in x").to_string(),
+        "let x = y ? 1 : 2 in x"
+    );
+}
+
+#[test]
+fn keywords_are_not_names() {
+    assert!(matches!(try_parse("let + 1"), Err(CalcError::Parse(_))));
+    assert!(matches!(try_parse("in = 1"), Err(CalcError::Parse(_))));
+    assert!(try_parse("letter + inside").is_ok());
+    assert!(matches!(
+        try_parse("let pi = 3 in pi"),
+        Err(CalcError::ReservedName(name)) if name == "pi"
+    ));
+}
+
+#[test]
+fn bound_names_are_not_free_variables() {
+    let expr = parse("let x = y in x * z");
+    assert_eq!(expr.variables(), ["y", "z"].map(String::from).into());
+}
+
+#[test]
+fn missing_in_is_reported() {
+    let e = try_parse("let x = 1").unwrap_err().to_string();
+    assert!(e.ends_with("expected `in`, `!`, `%`, or operator"), "{}", e);
+}
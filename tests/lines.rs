@@ -0,0 +1,203 @@
+use std::io::Cursor;
+
+use pest_calculator::{
+    eval_lines, read_entry, CalcError, Entry, Environment, EvalError, EvalErrorKind, EvalLines,
+    ParseConfig, Rule, Value,
+};
+
+#[test]
+fn yields_one_result_per_statement() {
+    let input = Cursor::new("1 + 2\nx = 4; x * 2\nx - 1\n");
+    let results = eval_lines(input).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        results,
+        [Value::Int(3), Value::Int(4), Value::Int(8), Value::Int(3)]
+    );
+}
+
+#[test]
+fn continues_after_a_failing_statement() {
+    let results: Vec<_> = eval_lines(Cursor::new("1 / 0; 2\n")).collect();
+    assert_eq!(results.len(), 2);
+    assert!(matches!(
+        results[0],
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        }))
+    ));
+    assert_eq!(results[1].as_ref().unwrap(), &Value::Int(2));
+}
+
+#[test]
+fn ans_is_the_result_of_the_previous_line() {
+    let mut env = Environment::new();
+    env.track_ans();
+    let input = Cursor::new("ans\n2 + 3\nans * 10; ans + 1\n1 / 0\n7; 1 / 0\nans\n");
+    let results: Vec<_> = EvalLines::new(&mut env, input)
+        .map(|line| {
+            let statements = line.unwrap().statements.unwrap();
+            statements
+                .into_iter()
+                .map(|statement| statement.result.unwrap().map_err(|e| e.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(
+        results,
+        [
+            vec![Err("ans used before any result".to_owned())],
+            vec![Ok(Value::Int(5))],
+            vec![Ok(Value::Int(50)), Ok(Value::Int(6))],
+            vec![Err("division by zero".to_owned())],
+            vec![Ok(Value::Int(7)), Err("division by zero".to_owned())],
+            vec![Ok(Value::Int(6))],
+        ]
+    );
+}
+
+#[test]
+fn evaluates_lines_in_a_shared_environment() {
+    let mut env = Environment::new();
+    env.track_ans();
+    let input = Cursor::new("x = 2 * (3 +\n4)\n\nx +\n");
+    let lines: Vec<_> = EvalLines::new(&mut env, input)
+        .continue_incomplete(|| {})
+        .steps()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        (lines[0].text.as_str(), lines[0].number),
+        ("x = 2 * (3 +\n4)", 1)
+    );
+    let statements = lines[0].statements.as_ref().unwrap();
+    assert_eq!(statements.len(), 1);
+    assert!(matches!(statements[0].result, Some(Ok(Value::Int(14)))));
+    assert!(!statements[0].steps.is_empty());
+    assert_eq!(lines[1].number, 4);
+    assert!(matches!(lines[1].statements, Err(CalcError::Parse(_))));
+    assert_eq!(env.eval_line("x + ans").unwrap(), Value::Int(28));
+
+    let lines: Vec<_> = EvalLines::new(&mut env, Cursor::new("y = 1; y\n"))
+        .parse_only()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let statements = lines[0].statements.as_ref().unwrap();
+    assert_eq!(statements.len(), 2);
+    assert!(statements
+        .iter()
+        .all(|statement| statement.result.is_none()));
+    assert!(env.eval_line("y").is_err());
+}
+
+#[test]
+fn continues_after_a_failing_line() {
+    let results: Vec<_> = eval_lines(Cursor::new("1 / 0\n2 +\n5")).collect();
+    assert_eq!(results.len(), 3);
+    assert!(matches!(
+        results[0],
+        Err(CalcError::Eval(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        }))
+    ));
+    assert!(matches!(results[1], Err(CalcError::Parse(_))));
+    assert_eq!(results[2].as_ref().unwrap(), &Value::Int(5));
+}
+
+#[test]
+fn detects_incomplete_input() {
+    let config = ParseConfig::default();
+    let incomplete = |input: &str| match config.parse(Rule::program, input) {
+        Err(e) => e.is_incomplete(input),
+        Ok(_) => panic!("{} should not parse", input),
+    };
+    for input in [
+        "2 * (3 +",
+        "max(1, ",
+        "(1",
+        "x =",
+        "let x = 1",
+        "sum(1..",
+        "1 + # more",
+    ] {
+        assert!(incomplete(input), "{} should be incomplete", input);
+    }
+    for input in ["1 2", "1 )", "2 +* 3", "", "  ", "@", "(1))"] {
+        assert!(!incomplete(input), "{} should be invalid", input);
+    }
+}
+
+#[test]
+fn continues_incomplete_lines() {
+    let mut input = Cursor::new("2 * (3 +\n4)\n5\n");
+    let mut continuations = 0;
+    let config = ParseConfig::default();
+    let mut next = || read_entry(&mut input, &config, || continuations += 1).unwrap();
+    let first = next();
+    assert_eq!(
+        first,
+        Some(Entry::Text {
+            text: "2 * (3 +\n4)".to_owned(),
+            lines: 2
+        })
+    );
+    let second = next();
+    assert_eq!(
+        second,
+        Some(Entry::Text {
+            text: "5".to_owned(),
+            lines: 1
+        })
+    );
+    assert_eq!(next(), None);
+    assert_eq!(continuations, 1);
+
+    let mut env = Environment::new();
+    assert_eq!(env.eval_line("2 * (3 +\n4)").unwrap(), Value::Int(14));
+    assert_eq!(env.eval_line("1 + # more\n2").unwrap(), Value::Int(3));
+}
+
+#[test]
+fn blank_line_cancels_a_continuation() {
+    let mut input = Cursor::new("max(1,\n\n3\n1 +\n");
+    let config = ParseConfig::default();
+    let mut next = || read_entry(&mut input, &config, || {}).unwrap();
+    assert_eq!(next(), Some(Entry::Cancelled { lines: 2 }));
+    assert_eq!(
+        next(),
+        Some(Entry::Text {
+            text: "3".to_owned(),
+            lines: 1
+        })
+    );
+    // The input ends before the last entry is complete, which leaves it to fail to parse.
+    assert_eq!(
+        next(),
+        Some(Entry::Text {
+            text: "1 +".to_owned(),
+            lines: 1
+        })
+    );
+    assert_eq!(next(), None);
+}
+
+#[test]
+fn skips_blank_lines() {
+    let results: Vec<_> = eval_lines(Cursor::new("\n1 + 1\n   \n\t\n2 +\n\n")).collect();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), &Value::Int(2));
+    assert!(matches!(results[1], Err(CalcError::Parse(_))));
+}
+
+#[test]
+fn whole_input_is_one_line() {
+    let lines: Vec<_> = EvalLines::new(Environment::new(), Cursor::new("max(1,\n2)\n* 3; 4\n"))
+        .whole_input()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].text, "max(1,\n2)\n* 3; 4");
+    assert_eq!(lines[0].statements.as_ref().unwrap().len(), 2);
+}
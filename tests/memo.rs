@@ -0,0 +1,120 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{eval_memoized, EnabledOperators, Environment, EvalConfig, Value};
+
+/// Evaluates `input` both ways in fresh environments with `x = 3`, showing the results and the variables after.
+fn both(input: &str, config: EvalConfig) -> [String; 2] {
+    let expr = parse(input);
+    let mut plain = Environment::with_config(config);
+    let mut memoized = Environment::with_config(config);
+    plain.set("x", Value::Int(3));
+    memoized.set("x", Value::Int(3));
+    [
+        format!("{:?} x = {:?}", plain.eval(&expr), plain.get("x")),
+        format!(
+            "{:?} x = {:?}",
+            memoized.eval_memoized(&expr),
+            memoized.get("x")
+        ),
+    ]
+}
+
+/// `operand + operand + ...` with `2^depth` copies of `operand`.
+fn repeated(operand: &str, depth: u32) -> String {
+    (0..depth).fold(operand.to_owned(), |expr, _| format!("({0}) + ({0})", expr))
+}
+
+#[test]
+fn agrees_with_eval() {
+    for input in [
+        "1 + 2 * 3",
+        "sqrt(2) * sqrt(2) + SQRT(2)",
+        "2 ^ 10 - 5! + (2 ^ 10 - 5!)",
+        "x * x + (x = x + 1) * x",
+        "(x = x * 2) + (x = x * 2)",
+        "let y = 2 + 2 in y * (2 + 2) + x",
+        "1 < 2 ? max(1, 2) : abs(-1)",
+        "50% + 50% + 0.5",
+        "true || 1 / 0 > 0",
+        "false && 1 / 0 > 0",
+        "0x10 + 16 + 0b10000",
+        "1.0 + 1 + 1.0",
+        "sum(1..x) + sum(1..3) * product(2..x)",
+    ] {
+        let [plain, memoized] = both(input, EvalConfig::default());
+        assert_eq!(plain, memoized, "for {}", input);
+    }
+}
+
+#[test]
+fn reports_the_same_errors() {
+    for input in [
+        "(1 + 1) / (1 - 1) + (1 + 1) / (1 - 1)",
+        "2 ^ 62 * 2 ^ 62",
+        "1 ? 2 : 3",
+        "(21)! + 1",
+        "y + y",
+        "sqrt(1, 2)",
+        "true + true",
+        "abs(1..2) + sum(1..2)",
+    ] {
+        let [plain, memoized] = both(input, EvalConfig::default());
+        assert_eq!(plain, memoized, "for {}", input);
+    }
+
+    let config = EvalConfig {
+        operators: EnabledOperators {
+            logical: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let [plain, memoized] = both("1 / 0 > 0 && true", config);
+    assert_eq!(plain, memoized);
+    assert!(plain.contains("OperatorDisabled"), "{}", plain);
+}
+
+#[test]
+fn evaluates_large_repeated_trees() {
+    let expr = parse(&repeated("max(sqrt(16), abs(-3)) * 2", 12));
+    assert_eq!(eval_memoized(&expr).unwrap(), Value::Float(8.0 * 4096.0));
+
+    let expr = parse(&repeated("x * 2", 10));
+    let mut env = Environment::new();
+    env.set("x", Value::Int(1));
+    assert_eq!(env.eval_memoized(&expr).unwrap(), Value::Int(2048));
+}
+
+#[test]
+fn counts_what_it_evaluates_towards_the_operation_limit() {
+    let limited = |max_operations| {
+        Environment::with_config(EvalConfig {
+            max_operations,
+            ..Default::default()
+        })
+    };
+    // Each of the ten levels adds its two copies once, rather than doubling the work below it.
+    let expr = parse(&repeated("1 + 2", 10));
+    assert!(limited(100).eval(&expr).is_err());
+    let mut env = limited(100);
+    assert_eq!(env.eval_memoized(&expr).unwrap(), Value::Int(3072));
+    // The count starts from zero for every evaluation.
+    assert_eq!(env.eval_memoized(&expr).unwrap(), Value::Int(3072));
+
+    for input in [
+        "1 + 2 + 3 + 4 + 5 + 6",
+        "sum(1..100)",
+        "let y = 1 in y + y + y + y",
+    ] {
+        let [plain, memoized] = both(
+            input,
+            EvalConfig {
+                max_operations: 8,
+                ..Default::default()
+            },
+        );
+        assert!(plain.contains("OperationLimitExceeded"), "{}", plain);
+        assert!(memoized.contains("OperationLimitExceeded"), "{}", memoized);
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{Environment, EvalConfig, EvalErrorKind, ModuloMode, Value};
+
+fn eval_with(modulo: ModuloMode, input: &str) -> Value {
+    let expr = parse(input);
+    Environment::with_config(EvalConfig {
+        modulo,
+        ..EvalConfig::default()
+    })
+    .eval(&expr)
+    .unwrap()
+}
+
+#[test]
+fn truncated_is_the_default() {
+    let expr = parse("-7 % 3");
+    assert_eq!(Environment::new().eval(&expr).unwrap(), Value::Int(-1));
+}
+
+#[test]
+fn truncated_takes_the_sign_of_the_left_operand() {
+    let truncated = |input| eval_with(ModuloMode::Truncated, input);
+    assert_eq!(truncated("7 % 3"), Value::Int(1));
+    assert_eq!(truncated("-7 % 3"), Value::Int(-1));
+    assert_eq!(truncated("7 % -3"), Value::Int(1));
+    assert_eq!(truncated("-7 % -3"), Value::Int(-1));
+    assert_eq!(truncated("-7.5 % 2"), Value::Float(-1.5));
+}
+
+#[test]
+fn euclidean_is_never_negative() {
+    let euclidean = |input| eval_with(ModuloMode::Euclidean, input);
+    assert_eq!(euclidean("7 % 3"), Value::Int(1));
+    assert_eq!(euclidean("-7 % 3"), Value::Int(2));
+    assert_eq!(euclidean("7 % -3"), Value::Int(1));
+    assert_eq!(euclidean("-7 % -3"), Value::Int(2));
+    assert_eq!(euclidean("-7.5 % 2"), Value::Float(0.5));
+}
+
+#[test]
+fn euclidean_still_rejects_zero() {
+    let expr = parse("-7 % 0");
+    let mut env = Environment::with_config(EvalConfig {
+        modulo: ModuloMode::Euclidean,
+        ..EvalConfig::default()
+    });
+    assert!(matches!(
+        env.eval(&expr).unwrap_err().kind,
+        EvalErrorKind::DivisionByZero
+    ));
+}
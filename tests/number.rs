@@ -0,0 +1,110 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{eval_as, Environment, EvalErrorKind, Int, Value};
+
+#[test]
+fn the_same_tree_evaluates_in_either_backend() {
+    let expr = parse("7 / 2 + 2 ^ 3 - -1");
+    assert_eq!(eval_as::<i64>(&expr).unwrap(), 3 + 8 + 1);
+    assert_eq!(eval_as::<f64>(&expr).unwrap(), 3.5 + 8.0 + 1.0);
+
+    let expr = parse("-7 % 3 * 2");
+    assert_eq!(eval_as::<Int>(&expr).unwrap(), -2);
+    assert_eq!(eval_as::<f64>(&expr).unwrap(), -2.0);
+}
+
+#[test]
+fn literals_are_converted_to_the_backend() {
+    assert_eq!(eval_as::<i64>(&parse("4.0 / 2")).unwrap(), 2);
+    assert!(matches!(
+        eval_as::<i64>(&parse("1 + 2.5")).unwrap_err().kind,
+        EvalErrorKind::Unsupported { number: "int", .. }
+    ));
+    assert!(matches!(
+        eval_as::<i64>(&parse("pi")).unwrap_err().kind,
+        EvalErrorKind::Unsupported { .. }
+    ));
+    assert_eq!(
+        eval_as::<f64>(&parse("2 * pi")).unwrap(),
+        2.0 * std::f64::consts::PI
+    );
+}
+
+#[test]
+fn each_backend_reports_its_own_errors() {
+    let expr = parse("9223372036854775807 + 1");
+    assert!(matches!(
+        eval_as::<i64>(&expr).unwrap_err().kind,
+        EvalErrorKind::Overflow("addition")
+    ));
+    assert_eq!(eval_as::<f64>(&expr).unwrap(), 9223372036854775808.0);
+
+    let expr = parse("2 ^ -1");
+    assert!(matches!(
+        eval_as::<i64>(&expr).unwrap_err().kind,
+        EvalErrorKind::Unsupported { .. }
+    ));
+    assert_eq!(eval_as::<f64>(&expr).unwrap(), 0.5);
+
+    for backend_error in [
+        eval_as::<i64>(&parse("1 / 0")).unwrap_err(),
+        eval_as::<f64>(&parse("1 / 0")).unwrap_err(),
+    ] {
+        assert!(matches!(backend_error.kind, EvalErrorKind::DivisionByZero));
+    }
+}
+
+#[test]
+fn variables_and_let_bindings_are_numbers_too() {
+    let mut env = Environment::new();
+    env.set("x", Value::Int(5));
+    env.set("half", Value::Float(0.5));
+    env.set("flag", Value::Bool(true));
+    assert_eq!(
+        env.eval_as::<i64>(&parse("let y = x * 2 in y - x"))
+            .unwrap(),
+        5
+    );
+    assert_eq!(env.eval_as::<f64>(&parse("x / 2 + half")).unwrap(), 3.0);
+    assert!(matches!(
+        env.eval_as::<i64>(&parse("half")).unwrap_err().kind,
+        EvalErrorKind::Unsupported { .. }
+    ));
+    assert!(matches!(
+        env.eval_as::<f64>(&parse("flag + 1")).unwrap_err().kind,
+        EvalErrorKind::Unsupported { .. }
+    ));
+    assert!(matches!(
+        env.eval_as::<f64>(&parse("z")).unwrap_err().kind,
+        EvalErrorKind::UndefinedVariable(_)
+    ));
+}
+
+#[test]
+fn non_numeric_expressions_are_unsupported() {
+    for input in [
+        "1 < 2",
+        "true",
+        "abs(1)",
+        "x = 1",
+        "1 ? 2 : 3",
+        "3!",
+        "50%",
+        "1 & 2",
+    ] {
+        let error = eval_as::<f64>(&parse(input)).unwrap_err();
+        assert!(
+            matches!(error.kind, EvalErrorKind::Unsupported { .. }),
+            "{}: {:?}",
+            input,
+            error
+        );
+    }
+    let error = eval_as::<i64>(&parse("1 + (2 < 3)")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "a comparison, logical or bitwise operator cannot be evaluated as int"
+    );
+    assert_eq!((error.span.start, error.span.end), (4, 11));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{to_rpn, Environment, EvalConfig, EvalError, EvalErrorKind, Value};
+
+fn eval_limited(max_operations: u64, input: &str) -> Result<Value, EvalError> {
+    Environment::with_config(EvalConfig {
+        max_operations,
+        ..EvalConfig::default()
+    })
+    .eval(&parse(input))
+}
+
+#[test]
+fn unlimited_by_default() {
+    let mut env = Environment::new();
+    assert_eq!(
+        env.eval(&parse("sum(1..1000000)")).unwrap(),
+        Value::Int(500_000_500_000)
+    );
+}
+
+#[test]
+fn every_node_counts_as_an_operation() {
+    // Five literals and four additions.
+    let input = "1 + 2 + 3 + 4 + 5";
+    assert_eq!(eval_limited(9, input).unwrap(), Value::Int(15));
+    let error = eval_limited(8, input).unwrap_err();
+    assert!(matches!(
+        error.kind,
+        EvalErrorKind::OperationLimitExceeded { max: 8 }
+    ));
+    assert_eq!(error.to_string(), "evaluation took more than 8 operations");
+}
+
+#[test]
+fn a_moderately_sized_expression_exceeds_a_low_limit() {
+    let input = vec!["(1 * 2 - 3)"; 50].join(" + ");
+    assert_eq!(eval_limited(1000, &input).unwrap(), Value::Int(-50));
+    assert!(matches!(
+        eval_limited(100, &input).unwrap_err().kind,
+        EvalErrorKind::OperationLimitExceeded { max: 100 }
+    ));
+}
+
+#[test]
+fn ranges_count_each_element() {
+    // The call, its two bounds and the ten elements.
+    assert_eq!(eval_limited(13, "sum(1..10)").unwrap(), Value::Int(55));
+    let error = eval_limited(12, "2 * sum(1..10)").unwrap_err();
+    assert!(matches!(
+        error.kind,
+        EvalErrorKind::OperationLimitExceeded { .. }
+    ));
+    assert_eq!((error.span.start, error.span.end), (4, 14));
+    assert_eq!(eval_limited(3, "product(5..1)").unwrap(), Value::Int(1));
+}
+
+#[test]
+fn ranges_above_the_range_limit_are_reported_as_such() {
+    // Both limits are exceeded, but the range is rejected before its elements are counted.
+    assert!(matches!(
+        eval_limited(100, "sum(1..2_000_000)").unwrap_err().kind,
+        EvalErrorKind::RangeTooLarge { max: 1_000_000 }
+    ));
+    assert!(matches!(
+        eval_limited(100, "sum(1..1_000)").unwrap_err().kind,
+        EvalErrorKind::OperationLimitExceeded { max: 100 }
+    ));
+}
+
+#[test]
+fn untaken_branches_do_not_count() {
+    let expensive = vec!["1"; 100].join(" + ");
+    let input = format!("true || ({}) > 0", expensive);
+    assert_eq!(eval_limited(3, &input).unwrap(), Value::Bool(true));
+    let input = format!("false ? {} : 0", expensive);
+    assert_eq!(eval_limited(3, &input).unwrap(), Value::Int(0));
+}
+
+#[test]
+fn the_count_starts_over_for_each_evaluation() {
+    let mut env = Environment::with_config(EvalConfig {
+        max_operations: 3,
+        ..EvalConfig::default()
+    });
+    for _ in 0..3 {
+        assert_eq!(env.eval(&parse("1 + 2")).unwrap(), Value::Int(3));
+    }
+    assert_eq!(env.eval_line("x = 3; x * 2").unwrap(), Value::Int(6));
+}
+
+#[test]
+fn every_evaluator_is_limited() {
+    let input = ["1"; 10].join(" + ");
+    let expr = parse(&input);
+    let env = || {
+        Environment::with_config(EvalConfig {
+            max_operations: 5,
+            ..EvalConfig::default()
+        })
+    };
+    let exceeded = |kind: EvalErrorKind| {
+        assert!(
+            matches!(kind, EvalErrorKind::OperationLimitExceeded { max: 5 }),
+            "{:?}",
+            kind
+        )
+    };
+    exceeded(env().eval(&expr).unwrap_err().kind);
+    exceeded(env().eval_steps(&expr).unwrap_err().kind);
+    exceeded(env().eval_rpn(&to_rpn(&expr)).unwrap_err());
+    exceeded(env().eval_as::<i64>(&expr).unwrap_err().kind);
+}
+
+#[test]
+fn every_evaluator_counts_from_zero_on_each_call() {
+    let expr = parse("1 + 2 * 3");
+    let mut env = Environment::with_config(EvalConfig {
+        max_operations: 15,
+        ..EvalConfig::default()
+    });
+    for _ in 0..3 {
+        assert_eq!(env.eval_steps(&expr).unwrap().0, Value::Int(7));
+        assert_eq!(env.eval_rpn(&to_rpn(&expr)).unwrap(), Value::Int(7));
+        assert_eq!(env.eval_as::<i64>(&expr).unwrap(), 7);
+    }
+}
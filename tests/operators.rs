@@ -0,0 +1,62 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{
+    EnabledOperators, Environment, EvalConfig, EvalError, EvalErrorKind, Op, Span, UnaryOp, Value,
+};
+
+/// A calculator without `%` and prefix `-`.
+fn restricted() -> Environment {
+    Environment::with_config(EvalConfig {
+        operators: EnabledOperators {
+            modulo: false,
+            unary_minus: false,
+            ..EnabledOperators::default()
+        },
+        ..EvalConfig::default()
+    })
+}
+
+fn eval(env: &mut Environment, input: &str) -> Result<Value, EvalError> {
+    env.eval(&parse(input))
+}
+
+#[test]
+fn everything_is_enabled_by_default() {
+    let mut env = Environment::new();
+    assert_eq!(eval(&mut env, "-7 % 3").unwrap(), Value::Int(-1));
+}
+
+#[test]
+fn rejects_disabled_operators() {
+    let mut env = restricted();
+    assert_eq!(eval(&mut env, "7 + 3").unwrap(), Value::Int(10));
+    let e = eval(&mut env, "1 + 7 % 3").unwrap_err();
+    assert!(matches!(
+        &e.kind,
+        EvalErrorKind::OperatorDisabled(Op::Modulo)
+    ));
+    assert_eq!(e.span, Span::new(4, 9));
+    assert_eq!(e.to_string(), "operator % is disabled");
+    assert!(matches!(
+        eval(&mut env, "2 * -x").unwrap_err().kind,
+        EvalErrorKind::UnaryOperatorDisabled(UnaryOp::Minus)
+    ));
+}
+
+#[test]
+fn keeps_related_operators() {
+    let mut env = restricted();
+    assert_eq!(eval(&mut env, "7 - 3").unwrap(), Value::Int(4));
+    assert_eq!(eval(&mut env, "50%").unwrap(), Value::Float(0.5));
+}
+
+#[test]
+fn rejects_before_evaluating_operands() {
+    let mut env = restricted();
+    assert!(matches!(
+        eval(&mut env, "(x = 1) % 2").unwrap_err().kind,
+        EvalErrorKind::OperatorDisabled(_)
+    ));
+    assert_eq!(env.get("x"), None);
+}
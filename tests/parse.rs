@@ -0,0 +1,248 @@
+mod common;
+
+use common::parse;
+use pest::Parser;
+use pest_calculator::{
+    eval, evaluate, Associativity, CalcError, CalculatorParser, ExprKind, Int, Op, Radix, Rule,
+    Value,
+};
+
+#[test]
+fn parses_with_precedence() {
+    let expr = parse("1 + 2 * 3");
+    match expr.kind {
+        ExprKind::BinOp {
+            lhs,
+            op: Op::Add,
+            rhs,
+        } => {
+            assert!(matches!(lhs.kind, ExprKind::Integer(1, _)));
+            assert!(matches!(
+                rhs.kind,
+                ExprKind::BinOp {
+                    op: Op::Multiply,
+                    ..
+                }
+            ));
+        }
+        other => panic!("expected an addition, found {:?}", other),
+    }
+}
+
+#[test]
+fn exposes_precedence_and_associativity() {
+    assert!(Op::Multiply.precedence() > Op::Add.precedence());
+    assert_eq!(Op::Multiply.precedence(), Op::Divide.precedence());
+    assert_eq!(Op::Divide.precedence(), Op::FloorDivide.precedence());
+    assert_eq!(Op::Add.associativity(), Associativity::Left);
+    assert_eq!(Op::Multiply.associativity(), Associativity::Left);
+    assert_eq!(Op::Power.associativity(), Associativity::Right);
+    assert!(Op::Power.precedence() > Op::Multiply.precedence());
+    assert!(Op::And.precedence() > Op::Or.precedence());
+}
+
+#[test]
+fn parses_and_evaluates() {
+    assert_eq!(eval(&parse("-(2 + 5) * 16")).unwrap(), Value::Int(-112));
+}
+
+#[test]
+fn unary_minus_binds_looser_than_power() {
+    for (input, expected) in [
+        ("-2 ^ 2", Value::Int(-4)),
+        ("(-2) ^ 2", Value::Int(4)),
+        ("-2 ^ -2", Value::Float(-0.25)),
+        ("2 ^ -1", Value::Float(0.5)),
+        ("2 ^ -2 ^ 2", Value::Float(0.0625)),
+        ("-2 ^ 2 * 3", Value::Int(-12)),
+        ("1 - -2 ^ 2", Value::Int(5)),
+        ("-2! ^ 2", Value::Int(-4)),
+    ] {
+        assert_eq!(eval(&parse(input)).unwrap(), expected, "for {}", input);
+    }
+}
+
+#[test]
+fn rejects_trailing_operator() {
+    assert!(CalculatorParser::parse(Rule::equation, "1 +").is_err());
+}
+
+#[test]
+fn parentheses_only_group() {
+    assert_eq!(format!("{:?}", parse("(5)")), "Integer(5, Decimal)");
+    assert_eq!(
+        format!("{:?}", parse("((2+3))")),
+        format!("{:?}", parse("2 + 3"))
+    );
+    assert_eq!(
+        format!("{:?}", parse("-(((x)))")),
+        "UnaryMinus(Variable(\"x\"))"
+    );
+}
+
+#[test]
+fn rejects_empty_parentheses() {
+    for input in ["()", "( )", "1 + ()", "max(())"] {
+        assert!(
+            matches!(evaluate(input), Err(CalcError::Parse(_))),
+            "parsing {:?}",
+            input
+        );
+    }
+}
+
+/// Pins the exact trees produced by parsing, which were first recorded with pest's `PrecClimber`,
+/// so that the move to its `PrattParser` and any later change of parsing strategy can be checked for regressions.
+#[test]
+fn parse_trees_are_stable() {
+    let cases = [
+        (
+            "1 - 2 + 3",
+            "BinOp { lhs: BinOp { lhs: Integer(1, Decimal), op: Subtract, rhs: Integer(2, Decimal) }, op: Add, rhs: Integer(3, Decimal) }",
+        ),
+        (
+            "1 + 2 * 3 % 4",
+            "BinOp { lhs: Integer(1, Decimal), op: Add, rhs: BinOp { lhs: BinOp { lhs: Integer(2, Decimal), op: Multiply, rhs: Integer(3, Decimal) }, op: Modulo, rhs: Integer(4, Decimal) } }",
+        ),
+        (
+            "2 ^ 3 ^ 2",
+            "BinOp { lhs: Integer(2, Decimal), op: Power, rhs: BinOp { lhs: Integer(3, Decimal), op: Power, rhs: Integer(2, Decimal) } }",
+        ),
+        (
+            "-(1.5 / 2)!",
+            "UnaryMinus(Factorial(BinOp { lhs: Float(1.5), op: Divide, rhs: Integer(2, Decimal) }))",
+        ),
+        (
+            "-2 ^ 2",
+            "UnaryMinus(BinOp { lhs: Integer(2, Decimal), op: Power, rhs: Integer(2, Decimal) })",
+        ),
+        (
+            "-2 ^ -2",
+            "UnaryMinus(BinOp { lhs: Integer(2, Decimal), op: Power, rhs: UnaryMinus(Integer(2, Decimal)) })",
+        ),
+        (
+            "2 ^ -1",
+            "BinOp { lhs: Integer(2, Decimal), op: Power, rhs: UnaryMinus(Integer(1, Decimal)) }",
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            format!("{:?}", parse(input)),
+            expected,
+            "parsing {:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn parses_prefixed_literals() {
+    assert!(matches!(
+        parse("0xFF").kind,
+        ExprKind::Integer(255, Radix::Hexadecimal)
+    ));
+    assert!(matches!(
+        parse("0o17").kind,
+        ExprKind::Integer(15, Radix::Octal)
+    ));
+    assert!(matches!(
+        parse("0b1010").kind,
+        ExprKind::Integer(10, Radix::Binary)
+    ));
+    assert!(matches!(
+        parse("0").kind,
+        ExprKind::Integer(0, Radix::Decimal)
+    ));
+    assert_eq!(evaluate("0xF + 0b1").unwrap(), Value::Int(16));
+}
+
+#[test]
+fn rejects_malformed_prefixed_literals() {
+    assert!(CalculatorParser::parse(Rule::equation, "0x").is_err());
+    assert!(CalculatorParser::parse(Rule::equation, "0b102").is_err());
+}
+
+#[test]
+fn parses_digit_separators() {
+    assert!(matches!(
+        parse("1_000_000").kind,
+        ExprKind::Integer(1_000_000, _)
+    ));
+    assert!(matches!(parse("1_0").kind, ExprKind::Integer(10, _)));
+    assert_eq!(evaluate("1_000.000_5").unwrap(), Value::Float(1000.0005));
+    assert_eq!(evaluate("1_0e1_0").unwrap(), Value::Float(1e11));
+}
+
+#[test]
+fn rejects_misplaced_digit_separators() {
+    for input in ["1__0", "5_", "1_000_", "1_.5", "1._5"] {
+        assert!(
+            CalculatorParser::parse(Rule::equation, input).is_err(),
+            "{:?} should not parse",
+            input
+        );
+    }
+    // `_5` is a valid variable name, but never a number.
+    assert!(CalculatorParser::parse(Rule::integer, "_5").is_err());
+    assert!(CalculatorParser::parse(Rule::decimal, "_5.0").is_err());
+}
+
+#[test]
+fn reports_prefixed_literals_out_of_range() {
+    match evaluate("0x10000000000000000") {
+        Err(CalcError::LiteralOutOfRange(literal)) => assert_eq!(literal, "0x10000000000000000"),
+        other => panic!("expected an out of range literal, found {:?}", other),
+    }
+}
+
+#[test]
+fn reports_decimal_literals_out_of_range() {
+    match evaluate("1 + 99999999999999999999") {
+        Err(CalcError::LiteralOutOfRange(literal)) => assert_eq!(literal, "99999999999999999999"),
+        other => panic!("expected an out of range literal, found {:?}", other),
+    }
+    assert_eq!(
+        evaluate("9223372036854775807").unwrap(),
+        Value::Int(Int::MAX)
+    );
+}
+
+#[test]
+fn skips_comments() {
+    assert_eq!(parse("1 + 2 # adds them").to_string(), "1 + 2");
+    assert_eq!(parse("7 // 2 # floor").to_string(), "7 // 2");
+    assert_eq!(parse("1 + /* x */ 2").to_string(), "1 + 2");
+    assert_eq!(parse("/* leading */ 3 * 4").to_string(), "3 * 4");
+}
+
+#[test]
+fn comment_only_line_has_no_expression() {
+    assert!(CalculatorParser::parse(Rule::equation, "# just a note").is_err());
+    assert!(CalculatorParser::parse(Rule::equation, "1 + /* unterminated 2").is_err());
+}
+
+// Line comments used to start with `//`, which is floor division now.
+#[test]
+fn double_slashes_are_no_longer_comments() {
+    assert_eq!(parse("1 + 2 // note").to_string(), "1 + 2 // note");
+    assert!(CalculatorParser::parse(Rule::equation, "1 + 2 // adds them").is_err());
+    assert!(CalculatorParser::parse(Rule::equation, "// just a note").is_err());
+}
+
+#[test]
+fn identical_parses_compare_equal() {
+    let input = "f(x, 2) ^ -y! + (a ? 1.5 : 0x1f) % 3";
+    let expr = parse(input);
+    assert_eq!(expr, parse(input));
+    assert_ne!(expr, parse("f(x, 2) ^ -y! + (a ? 1.5 : 0x1f) % 4"));
+
+    let copy = expr.clone();
+    assert_eq!(copy, expr);
+    match (&copy.kind, &expr.kind) {
+        (ExprKind::BinOp { op, .. }, ExprKind::BinOp { op: other, .. }) => {
+            assert_eq!(op, other);
+            assert_eq!(op.clone(), Op::Add);
+        }
+        _ => panic!("expected an addition, got {:?}", copy),
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{evaluate, ExprKind, Op, Value};
+
+#[test]
+fn divides_by_one_hundred() {
+    assert_eq!(evaluate("50%").unwrap(), Value::Float(0.5));
+    assert_eq!(evaluate("2.5%").unwrap(), Value::Float(0.025));
+}
+
+#[test]
+fn binds_tighter_than_infix_operators() {
+    assert_eq!(evaluate("200 * 10%").unwrap(), Value::Float(20.0));
+    assert_eq!(evaluate("10% - 3").unwrap(), Value::Float(-2.9));
+    assert_eq!(evaluate("(1 + 1)%").unwrap(), Value::Float(0.02));
+}
+
+#[test]
+fn is_modulo_when_an_operand_follows() {
+    assert_eq!(evaluate("7 % 3").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("7%3").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("7 % -3").unwrap(), Value::Int(1));
+    assert_eq!(evaluate("7 % (2 + 1)").unwrap(), Value::Int(1));
+    assert!(matches!(
+        parse("7 % 3").kind,
+        ExprKind::BinOp { op: Op::Modulo, .. }
+    ));
+}
+
+#[test]
+fn can_be_followed_by_modulo() {
+    assert!(matches!(
+        parse("50% % 3").kind,
+        ExprKind::BinOp { lhs, op: Op::Modulo, .. } if matches!(lhs.kind, ExprKind::Percent(_))
+    ));
+}
+
+#[test]
+fn is_displayed_as_postfix() {
+    assert_eq!(parse("(200 * 10)% - 1").to_string(), "(200 * 10)% - 1");
+}
+
+#[test]
+fn rejects_booleans() {
+    assert!(evaluate("true%").is_err());
+}
+
+#[test]
+fn can_be_followed_by_factorial() {
+    assert!(
+        matches!(parse("5%!").kind, ExprKind::Factorial(operand) if matches!(operand.kind, ExprKind::Percent(_)))
+    );
+    assert!(matches!(
+        parse("7 % !x").kind,
+        ExprKind::BinOp { op: Op::Modulo, .. }
+    ));
+}
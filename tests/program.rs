@@ -0,0 +1,50 @@
+mod common;
+
+use common::statements;
+use pest::Parser;
+use pest_calculator::{CalculatorParser, Environment, EvalError, EvalErrorKind, Rule, Value};
+
+fn run(input: &str) -> Vec<Result<Value, EvalError>> {
+    let statements = statements(input);
+    let mut env = Environment::new();
+    statements
+        .iter()
+        .map(|statement| env.eval(statement))
+        .collect()
+}
+
+#[test]
+fn evaluates_each_statement() {
+    let results = run("1 + 2; 3 * 4");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), &Value::Int(3));
+    assert_eq!(results[1].as_ref().unwrap(), &Value::Int(12));
+}
+
+#[test]
+fn allows_trailing_semicolon() {
+    let results = run("x = 2; x * x;");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].as_ref().unwrap(), &Value::Int(4));
+}
+
+#[test]
+fn continues_after_an_error() {
+    let results = run("1; 1 / 0; 2");
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap(), &Value::Int(1));
+    assert!(matches!(
+        results[1],
+        Err(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        })
+    ));
+    assert_eq!(results[2].as_ref().unwrap(), &Value::Int(2));
+}
+
+#[test]
+fn rejects_empty_statements() {
+    assert!(CalculatorParser::parse(Rule::program, ";").is_err());
+    assert!(CalculatorParser::parse(Rule::program, "1;; 2").is_err());
+}
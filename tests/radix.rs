@@ -0,0 +1,35 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{eval, fold_constants, format_result, Radix};
+
+/// Evaluates `input` and formats the result in the radix its literals suggest.
+fn result(input: &str) -> String {
+    let expr = parse(input);
+    format_result(&eval(&expr).unwrap(), expr.preferred_radix()).unwrap()
+}
+
+#[test]
+fn hex_literals_give_hex_results() {
+    assert_eq!(result("0xFF + 1"), "0x100");
+    assert_eq!(result("255 + 1"), "256");
+    assert_eq!(result("1 + 0xff"), "0x100");
+    assert_eq!(result("0x10 - 0x20"), "-0x10");
+}
+
+#[test]
+fn hex_wins_over_other_radixes() {
+    assert_eq!(parse("0b1 + 0xF").preferred_radix(), Radix::Hexadecimal);
+    assert_eq!(parse("0o7 + 0b1").preferred_radix(), Radix::Binary);
+    assert_eq!(parse("0o7 + 1").preferred_radix(), Radix::Octal);
+    assert_eq!(parse("x + 1").preferred_radix(), Radix::Decimal);
+}
+
+#[test]
+fn literals_keep_their_radix_when_displayed() {
+    assert_eq!(
+        parse("0xFF + 0b11 * 0o17").to_string(),
+        "0xff + 0b11 * 0o17"
+    );
+    assert_eq!(fold_constants(parse("0xF0 | 15")).to_string(), "0xff");
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::parse;
+use pest::Parser;
+use pest_calculator::{
+    eval_steps, evaluate, fold_constants, CalcError, CalculatorParser, Environment, EvalConfig,
+    EvalErrorKind, ExprKind, Rule, Span, Value,
+};
+
+fn eval_error(input: &str) -> EvalErrorKind {
+    match evaluate(input) {
+        Err(CalcError::Eval(e)) => e.kind,
+        result => panic!(
+            "expected an evaluation error for {}, got {:?}",
+            input, result
+        ),
+    }
+}
+
+#[test]
+fn sums_and_multiplies_inclusive_ranges() {
+    assert_eq!(evaluate("sum(1..10)").unwrap(), Value::Int(55));
+    assert_eq!(evaluate("product(1..5)").unwrap(), Value::Int(120));
+    assert_eq!(evaluate("sum(-3..3)").unwrap(), Value::Int(0));
+    assert_eq!(evaluate("SUM(1 + 1..2 * 2)").unwrap(), Value::Int(9));
+    assert_eq!(evaluate("sum(5..5)").unwrap(), Value::Int(5));
+}
+
+#[test]
+fn empty_ranges_give_the_identity() {
+    assert_eq!(evaluate("sum(10..1)").unwrap(), Value::Int(0));
+    assert_eq!(evaluate("product(1..0)").unwrap(), Value::Int(1));
+}
+
+#[test]
+fn ends_can_be_variables() {
+    let mut env = Environment::new();
+    env.set("n", Value::Int(4));
+    assert_eq!(
+        env.eval(&parse("product(1..n) / sum(1..n)")).unwrap(),
+        Value::Int(2)
+    );
+}
+
+#[test]
+fn parses_a_range_as_the_only_argument() {
+    let expr = parse("sum(1..10)");
+    let (name, args) = match &expr.kind {
+        ExprKind::Call { name, args } => (name, args),
+        _ => panic!("expected a call, got {:?}", expr),
+    };
+    assert_eq!(name, "sum");
+    assert!(matches!(args[0].kind, ExprKind::Range { .. }));
+    assert_eq!(args[0].span, Span::new(4, 9));
+    assert_eq!(expr.to_string(), "sum(1..10)");
+
+    for input in [
+        "1..10",
+        "sum(1..2, 3)",
+        "sum(1, 2..3)",
+        "sum(..3)",
+        "sum(1..)",
+    ] {
+        assert!(
+            CalculatorParser::parse(Rule::equation, input).is_err(),
+            "{} should not parse",
+            input
+        );
+    }
+}
+
+#[test]
+fn rejects_misused_ranges() {
+    assert!(matches!(
+        eval_error("abs(1..3)"),
+        EvalErrorKind::MisplacedRange
+    ));
+    assert!(matches!(eval_error("sum(1, 2)"), EvalErrorKind::ExpectedRange(name) if name == "sum"));
+    assert!(matches!(
+        eval_error("f(1..3)"),
+        EvalErrorKind::UnknownFunction(_)
+    ));
+    assert!(matches!(
+        eval_error("sum(1..2.5)"),
+        EvalErrorKind::TypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn overflows_like_the_operators() {
+    assert_eq!(
+        eval_error("product(1..21)").to_string(),
+        "integer overflow in multiplication"
+    );
+}
+
+#[test]
+fn rejects_ranges_above_the_limit() {
+    let kind = eval_error("sum(1..1_000_001)");
+    assert!(matches!(
+        kind,
+        EvalErrorKind::RangeTooLarge { max: 1_000_000 }
+    ));
+    assert_eq!(kind.to_string(), "range has more than 1000000 elements");
+    assert_eq!(
+        evaluate("sum(1..1_000_000)").unwrap(),
+        Value::Int(500_000_500_000)
+    );
+    assert!(matches!(
+        eval_error("sum(-9223372036854775807..9223372036854775807)"),
+        EvalErrorKind::RangeTooLarge { .. }
+    ));
+
+    let mut env = Environment::with_config(EvalConfig {
+        max_range: 3,
+        ..Default::default()
+    });
+    assert_eq!(env.eval(&parse("sum(1..3)")).unwrap(), Value::Int(6));
+    assert!(env.eval(&parse("sum(1..4)")).is_err());
+}
+
+#[test]
+fn folds_and_steps_through_ranges() {
+    assert_eq!(
+        fold_constants(parse("sum(1..2 + 2) + x")).to_string(),
+        "10 + x"
+    );
+    let (value, steps) = eval_steps(&parse("sum(1..2 + 2) * 2")).unwrap();
+    assert_eq!(value, Value::Int(20));
+    assert_eq!(
+        steps,
+        ["sum(1..2 + 2) * 2", "sum(1..4) * 2", "10 * 2", "20"]
+    );
+}
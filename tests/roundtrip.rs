@@ -0,0 +1,250 @@
+//! Checks that printing a parsed expression and parsing it again gives the same tree,
+//! for many randomly generated inputs.
+
+mod common;
+
+use common::{parse, try_parse};
+
+/// A xorshift generator, so that every run checks the same inputs.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+/// The shape of a generated input, which is kept so that a failing input can be shrunk.
+#[derive(Clone, Debug)]
+enum Node {
+    Leaf(&'static str),
+    Prefix(&'static str, Box<Node>),
+    Postfix(Box<Node>, &'static str),
+    Infix(Box<Node>, &'static str, Box<Node>),
+    Call(&'static str, Vec<Node>),
+    Conditional(Box<Node>, Box<Node>, Box<Node>),
+    Assign(&'static str, Box<Node>),
+    Let(&'static str, Box<Node>, Box<Node>),
+    /// `sum` or `product` of a range.
+    Aggregate(&'static str, Box<Node>, Box<Node>),
+    Parenthesized(Box<Node>),
+}
+
+const LEAVES: &[&str] = &[
+    "0",
+    "7",
+    "42",
+    "1_000",
+    "0xff",
+    "0b101",
+    "0o17",
+    "2.5",
+    "1e3",
+    "1.5e-3",
+    "1e400",
+    "true",
+    "false",
+    "x",
+    "y1",
+    "_tmp",
+    "pi",
+    "e",
+    "9223372036854775807",
+    "0x7fffffffffffffff",
+];
+const INFIX: &[&str] = &[
+    "+", "-", "*", "/", "//", "%", "^", "<", "<=", ">", ">=", "==", "!=", "&&", "||", "&", "|",
+    "~", "<<", ">>",
+];
+const FUNCTIONS: &[(&str, usize)] = &[("abs", 1), ("sqrt", 1), ("max", 2), ("pow", 2), ("f", 0)];
+
+fn generate(rng: &mut Rng, depth: usize) -> Node {
+    if depth == 0 || rng.below(4) == 0 {
+        return Node::Leaf(rng.pick(LEAVES));
+    }
+    let depth = depth - 1;
+    match rng.below(10) {
+        0 => Node::Prefix(rng.pick(&["-", "!"]), Box::new(generate(rng, depth))),
+        1 => Node::Postfix(Box::new(generate(rng, depth)), rng.pick(&["!", "%"])),
+        2 | 3 => Node::Infix(
+            Box::new(generate(rng, depth)),
+            rng.pick(INFIX),
+            Box::new(generate(rng, depth)),
+        ),
+        4 => {
+            let (name, arity) = rng.pick(FUNCTIONS);
+            Node::Call(name, (0..arity).map(|_| generate(rng, depth)).collect())
+        }
+        5 => Node::Conditional(
+            Box::new(generate(rng, depth)),
+            Box::new(generate(rng, depth)),
+            Box::new(generate(rng, depth)),
+        ),
+        6 => Node::Assign(rng.pick(&["x", "y1"]), Box::new(generate(rng, depth))),
+        7 => Node::Let(
+            rng.pick(&["x", "y1"]),
+            Box::new(generate(rng, depth)),
+            Box::new(generate(rng, depth)),
+        ),
+        8 => Node::Aggregate(
+            rng.pick(&["sum", "product"]),
+            Box::new(generate(rng, depth)),
+            Box::new(generate(rng, depth)),
+        ),
+        _ => Node::Parenthesized(Box::new(generate(rng, depth))),
+    }
+}
+
+/// Whether `node` can be used as an operand of a postfix operator without parentheses.
+fn is_postfix_operand(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Leaf(_)
+            | Node::Call(..)
+            | Node::Aggregate(..)
+            | Node::Parenthesized(_)
+            | Node::Postfix(..)
+    )
+}
+
+/// Whether `node` can be used as an operand of a prefix or infix operator without parentheses.
+fn is_atom(node: &Node) -> bool {
+    is_postfix_operand(node) || matches!(node, Node::Prefix(..))
+}
+
+fn operand(node: &Node, bare: bool) -> String {
+    if bare {
+        render(node)
+    } else {
+        format!("({})", render(node))
+    }
+}
+
+/// Writes `node` with parentheses around every compound operand.
+fn render(node: &Node) -> String {
+    match node {
+        Node::Leaf(leaf) => leaf.to_string(),
+        Node::Prefix(op, operand_node) => {
+            format!("{}{}", op, operand(operand_node, is_atom(operand_node)))
+        }
+        Node::Postfix(operand_node, op) => {
+            format!(
+                "{}{}",
+                operand(operand_node, is_postfix_operand(operand_node)),
+                op
+            )
+        }
+        Node::Infix(lhs, op, rhs) => format!(
+            "{} {} {}",
+            operand(lhs, is_atom(lhs)),
+            op,
+            operand(rhs, is_atom(rhs))
+        ),
+        Node::Call(name, args) => {
+            let args: Vec<_> = args.iter().map(render).collect();
+            format!("{}({})", name, args.join(", "))
+        }
+        Node::Conditional(cond, then, otherwise) => format!(
+            "{} ? {} : {}",
+            operand(cond, is_atom(cond)),
+            operand(then, is_atom(then)),
+            operand(otherwise, is_atom(otherwise))
+        ),
+        Node::Assign(name, value) => format!("{} = {}", name, render(value)),
+        Node::Let(name, value, body) => {
+            format!("let {} = {} in {}", name, render(value), render(body))
+        }
+        Node::Aggregate(name, start, end) => {
+            format!("{}({}..{})", name, render(start), render(end))
+        }
+        Node::Parenthesized(inner) => format!("({})", render(inner)),
+    }
+}
+
+/// Smaller variants of `node`: each of its operands, and `node` with one operand shrunk.
+fn shrink(node: &Node) -> Vec<Node> {
+    let children: Vec<&Node> = match node {
+        Node::Leaf(_) => vec![],
+        Node::Prefix(_, a) | Node::Postfix(a, _) | Node::Assign(_, a) | Node::Parenthesized(a) => {
+            vec![a]
+        }
+        Node::Infix(a, _, b) | Node::Let(_, a, b) | Node::Aggregate(_, a, b) => vec![a, b],
+        Node::Call(_, args) => args.iter().collect(),
+        Node::Conditional(a, b, c) => vec![a, b, c],
+    };
+    let mut smaller: Vec<Node> = children.iter().map(|&child| child.clone()).collect();
+    for (i, child) in children.iter().enumerate() {
+        for shrunk in shrink(child) {
+            smaller.push(replace_child(node, i, shrunk));
+        }
+    }
+    smaller
+}
+
+/// `node` with its `i`th operand replaced by `child`.
+fn replace_child(node: &Node, i: usize, child: Node) -> Node {
+    let mut node = node.clone();
+    let child = Box::new(child);
+    match &mut node {
+        Node::Leaf(_) => unreachable!("leaves have no operands"),
+        Node::Prefix(_, a) | Node::Postfix(a, _) | Node::Assign(_, a) | Node::Parenthesized(a) => {
+            *a = child
+        }
+        Node::Infix(a, _, b) | Node::Let(_, a, b) | Node::Aggregate(_, a, b) => *[a, b][i] = child,
+        Node::Call(_, args) => args[i] = *child,
+        Node::Conditional(a, b, c) => *[a, b, c][i] = child,
+    }
+    node
+}
+
+/// Describes how `input` fails to round trip, if it does.
+fn roundtrip_failure(input: &str) -> Option<String> {
+    let expr = match try_parse(input) {
+        Ok(expr) => expr,
+        Err(e) => return Some(format!("{:?} does not parse: {}", input, e)),
+    };
+    let printed = expr.to_string();
+    match try_parse(&printed) {
+        Ok(reparsed) if reparsed.structurally_eq(&expr) => None,
+        Ok(reparsed) => Some(format!(
+            "{:?} is printed as {:?}, which parses as {:?} rather than {:?}",
+            input, printed, reparsed, expr
+        )),
+        Err(e) => Some(format!(
+            "{:?} is printed as {:?}, which does not parse: {}",
+            input, printed, e
+        )),
+    }
+}
+
+#[test]
+fn printed_expressions_parse_to_the_same_tree() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..2000 {
+        let mut node = generate(&mut rng, 5);
+        if roundtrip_failure(&render(&node)).is_none() {
+            continue;
+        }
+        while let Some(smaller) = shrink(&node)
+            .into_iter()
+            .find(|smaller| roundtrip_failure(&render(smaller)).is_some())
+        {
+            node = smaller;
+        }
+        panic!("{}", roundtrip_failure(&render(&node)).unwrap());
+    }
+}
+
+#[test]
+fn structural_equality_ignores_spans() {
+    assert!(try_parse("1 + 2").unwrap().structurally_eq(&parse("(1)+2")));
+    assert!(!try_parse("1 + 2").unwrap().structurally_eq(&parse("2 + 1")));
+    assert!(!try_parse("0xff").unwrap().structurally_eq(&parse("255")));
+}
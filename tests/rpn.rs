@@ -0,0 +1,129 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{
+    eval, eval_rpn, to_rpn, try_to_rpn, Environment, EvalErrorKind, Op, Token, Value,
+};
+
+fn num(i: i64) -> Token {
+    Token::Num(Value::Int(i))
+}
+
+#[test]
+fn lists_operands_before_their_operators() {
+    assert_eq!(
+        to_rpn(&parse("2 + 3 * 4")),
+        [
+            num(2),
+            num(3),
+            num(4),
+            Token::Op(Op::Multiply),
+            Token::Op(Op::Add)
+        ]
+    );
+    assert_eq!(
+        to_rpn(&parse("(2 + 3) * 4")),
+        [
+            num(2),
+            num(3),
+            Token::Op(Op::Add),
+            num(4),
+            Token::Op(Op::Multiply)
+        ]
+    );
+    assert_eq!(
+        to_rpn(&parse("-x! + max(1, 2.5)")),
+        [
+            Token::Variable("x".to_owned()),
+            Token::Factorial,
+            Token::UnaryNeg,
+            num(1),
+            Token::Num(Value::Float(2.5)),
+            Token::Call {
+                name: "max".to_owned(),
+                args: 2
+            },
+            Token::Op(Op::Add),
+        ]
+    );
+}
+
+#[test]
+fn agrees_with_evaluating_the_tree() {
+    for input in [
+        "2 + 3 * 4",
+        "2 ^ 3 ^ 2",
+        "10 - 4 - 3",
+        "-2 ^ 2 * 3!",
+        "7 / 2 + 7.0 / 2",
+        "max(1, 7, 3) - abs(-4) + sqrt(16)",
+        "200 * 10% + pi",
+        "!(1 < 2) || 3 >= 3",
+        "0xff & 0b1010 << 2",
+    ] {
+        let expr = parse(input);
+        let tokens = to_rpn(&expr);
+        assert_eq!(
+            eval_rpn(&tokens).unwrap(),
+            eval(&expr).unwrap(),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn reports_errors_like_evaluating_the_tree() {
+    let tokens = to_rpn(&parse("1 + 2 / (3 - 3)"));
+    assert!(matches!(
+        eval_rpn(&tokens).unwrap_err(),
+        EvalErrorKind::DivisionByZero
+    ));
+    let tokens = to_rpn(&parse("x * 2"));
+    assert!(matches!(
+        eval_rpn(&tokens).unwrap_err(),
+        EvalErrorKind::UndefinedVariable(_)
+    ));
+    let mut env = Environment::new();
+    env.set("x", Value::Int(21));
+    assert_eq!(env.eval_rpn(&tokens).unwrap(), Value::Int(42));
+}
+
+#[test]
+fn rejects_tokens_that_are_not_one_expression() {
+    for tokens in [
+        &[][..],
+        &[num(1), num(2)],
+        &[num(1), Token::Op(Op::Add)],
+        &[Token::Call {
+            name: "max".to_owned(),
+            args: 1,
+        }],
+    ] {
+        assert!(matches!(
+            eval_rpn(tokens).unwrap_err(),
+            EvalErrorKind::MalformedRpn
+        ));
+    }
+}
+
+#[test]
+fn has_no_tokens_for_branches_and_bindings() {
+    for input in [
+        "1 ? 2 : 3",
+        "x = 1",
+        "let x = 1 in x",
+        "sum(1..3)",
+        "2 * (y = 3)",
+    ] {
+        assert_eq!(try_to_rpn(&parse(input)), None, "{}", input);
+    }
+    let expr = parse("2 ^ -x");
+    assert_eq!(try_to_rpn(&expr), Some(to_rpn(&expr)));
+}
+
+#[test]
+#[should_panic(expected = "has no tokens")]
+fn to_rpn_panics_without_tokens() {
+    to_rpn(&parse("1 ? 2 : 3"));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::parse;
+use pest_calculator::{evaluate, CalcError, Expr};
+
+#[test]
+fn expressions_round_trip_through_json() {
+    for input in [
+        "1 + 2 * -x",
+        "0x1f << 2",
+        "max(1, 2.5) ? pi : e!",
+        "let y = 2 in y ^ y",
+        "sum(1..10)",
+    ] {
+        let expr = parse(input);
+        let json = serde_json::to_string(&expr).unwrap();
+        let decoded: Expr = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, expr, "{}", json);
+    }
+}
+
+#[test]
+fn values_serialize_by_type() {
+    let value = evaluate("7 / 2").unwrap();
+    assert_eq!(
+        serde_json::to_value(value).unwrap(),
+        serde_json::json!({"Int": 3})
+    );
+    let value = evaluate("1.5 * 2").unwrap();
+    assert_eq!(
+        serde_json::to_value(value).unwrap(),
+        serde_json::json!({"Float": 3.0})
+    );
+}
+
+#[test]
+fn errors_serialize() {
+    let error = evaluate("1 + 2 / 0").unwrap_err();
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({"Eval": {"kind": "DivisionByZero", "span": {"start": 4, "end": 9}}})
+    );
+    let error = evaluate("1 +").unwrap_err();
+    assert!(matches!(error, CalcError::Parse(_)));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["Parse"],
+        error.to_string().trim_start_matches("parse error\n")
+    );
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{simplify, Environment, Expr, ExprKind};
+
+fn simplified(input: &str) -> Expr {
+    simplify(parse(input))
+}
+
+#[test]
+fn even_negations_of_a_literal_cancel() {
+    assert!(matches!(simplified("--5").kind, ExprKind::Integer(5, _)));
+    assert!(matches!(simplified("----5").kind, ExprKind::Integer(5, _)));
+    assert_eq!(simplified("--2.5").to_string(), "2.5");
+}
+
+#[test]
+fn odd_negations_of_a_literal_become_a_negative_literal() {
+    assert!(matches!(simplified("---5").kind, ExprKind::Integer(-5, _)));
+    assert!(matches!(simplified("-5").kind, ExprKind::Integer(-5, _)));
+    assert_eq!(simplified("---5").to_string(), "-5");
+    assert_eq!(simplified("-0x10").to_string(), "-0x10");
+    assert_eq!(simplified("---2.5").to_string(), "-2.5");
+}
+
+#[test]
+fn negations_of_other_expressions_are_collapsed_but_kept() {
+    let expr = simplified("-(2+3)");
+    match expr.kind {
+        ExprKind::UnaryMinus(operand) => {
+            assert!(matches!(operand.kind, ExprKind::BinOp { .. }))
+        }
+        kind => panic!("expected a negation, got {:?}", kind),
+    }
+    assert_eq!(simplified("-(2+3)").to_string(), "-(2 + 3)");
+    assert_eq!(simplified("---x").to_string(), "-x");
+    assert_eq!(simplified("-----x").to_string(), "-x");
+    // `--x` fails for bools, so two negations are kept.
+    assert_eq!(simplified("----x").to_string(), "--x");
+}
+
+#[test]
+fn negations_are_simplified_everywhere() {
+    assert_eq!(
+        simplified("max(--1, 2) + ---y").to_string(),
+        "max(1, 2) + -y"
+    );
+    assert_eq!(simplified("-(--3 * x)").to_string(), "-(3 * x)");
+    assert_eq!(simplified("x = --4").to_string(), "x = 4");
+}
+
+#[test]
+fn results_are_unchanged() {
+    for input in [
+        "--5",
+        "---5",
+        "-(2+3)",
+        "---(7 - 10) * --2",
+        "-----2.5 ^ 2",
+        "--x + ---x",
+        "--b",
+        "-(-9223372036854775807 - 1)",
+        "--(-9223372036854775807 - 1)",
+    ] {
+        let mut env = Environment::new();
+        env.eval(&parse("x = 3")).unwrap();
+        env.eval(&parse("b = true")).unwrap();
+        let expected = format!("{:?}", env.eval(&parse(input)).map_err(|e| e.kind));
+        let actual = format!("{:?}", env.eval(&simplified(input)).map_err(|e| e.kind));
+        assert_eq!(actual, expected, "{}", input);
+    }
+}
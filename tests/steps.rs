@@ -0,0 +1,63 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{eval_steps, Environment, EvalError, EvalErrorKind, Value};
+
+#[test]
+fn reduces_innermost_first() {
+    assert_eq!(
+        eval_steps(&parse("2 + 3 * 4")).unwrap(),
+        (
+            Value::Int(14),
+            vec!["2 + 3 * 4".to_owned(), "2 + 12".to_owned(), "14".to_owned()]
+        )
+    );
+}
+
+#[test]
+fn reduces_leftmost_first() {
+    let (value, steps) = eval_steps(&parse("(1 + 2) * (3 - -1)")).unwrap();
+    assert_eq!(value, Value::Int(12));
+    assert_eq!(steps, ["(1 + 2) * (3 - -1)", "3 * (3 - -1)", "3 * 4", "12"]);
+}
+
+#[test]
+fn a_value_takes_no_steps() {
+    assert_eq!(
+        eval_steps(&parse("5")).unwrap(),
+        (Value::Int(5), vec!["5".to_owned()])
+    );
+}
+
+#[test]
+fn substitutes_variables_and_takes_one_branch() {
+    let mut env = Environment::new();
+    env.set("x", Value::Int(3));
+    let (value, steps) = env
+        .eval_steps(&parse("x > 2 || 1 / 0 > 1 ? x! : 0"))
+        .unwrap();
+    assert_eq!(value, Value::Int(6));
+    assert_eq!(
+        steps,
+        [
+            "x > 2 || 1 / 0 > 1 ? x! : 0",
+            "3 > 2 || 1 / 0 > 1 ? x! : 0",
+            "true || 1 / 0 > 1 ? x! : 0",
+            "true ? x! : 0",
+            "x!",
+            "3!",
+            "6",
+        ]
+    );
+}
+
+#[test]
+fn stops_at_the_first_error() {
+    assert!(matches!(
+        eval_steps(&parse("1 + 2 / 0")),
+        Err(EvalError {
+            kind: EvalErrorKind::DivisionByZero,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,78 @@
+use pest_calculator::{tokens, CalcError, ParseConfig, Rule, Span, SpannedToken};
+
+fn listed(input: &str) -> Vec<String> {
+    tokens(input)
+        .unwrap()
+        .iter()
+        .map(|token| token.to_string())
+        .collect()
+}
+
+#[test]
+fn lists_each_token_with_its_span() {
+    assert_eq!(
+        tokens("12 + 3").unwrap(),
+        [
+            SpannedToken {
+                rule: Rule::integer,
+                span: Span::new(0, 2)
+            },
+            SpannedToken {
+                rule: Rule::add,
+                span: Span::new(3, 4)
+            },
+            SpannedToken {
+                rule: Rule::integer,
+                span: Span::new(5, 6)
+            },
+        ]
+    );
+    assert_eq!(
+        listed("12 + 3"),
+        ["integer@0..2", "add@3..4", "integer@5..6"]
+    );
+}
+
+#[test]
+fn skips_whitespace_comments_and_punctuation() {
+    assert_eq!(
+        listed("max(x, 2.5) /* c */ >= 0xff # done"),
+        [
+            "identifier@0..3",
+            "identifier@4..5",
+            "decimal@7..10",
+            "greater_equal@20..22",
+            "hexadecimal@23..27",
+        ]
+    );
+    assert_eq!(
+        listed("let y = -1 in y!; true"),
+        [
+            "identifier@4..5",
+            "integer@9..10",
+            "identifier@14..15",
+            "factorial@15..16",
+            "boolean@18..22",
+        ]
+    );
+}
+
+#[test]
+fn implicit_multiplication_has_no_token() {
+    let config = ParseConfig {
+        implicit_multiplication: true,
+        ..ParseConfig::default()
+    };
+    let tokens: Vec<_> = config
+        .tokens("2x")
+        .unwrap()
+        .iter()
+        .map(|token| token.to_string())
+        .collect();
+    assert_eq!(tokens, ["integer@0..1", "identifier@1..2"]);
+}
+
+#[test]
+fn fails_for_input_that_does_not_parse() {
+    assert!(matches!(tokens("1 +"), Err(CalcError::Parse(_))));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::parse;
+use pest_calculator::render_tree;
+
+fn tree(input: &str) -> String {
+    render_tree(&parse(input))
+}
+
+#[test]
+fn renders_operands_below_their_operator() {
+    assert_eq!(
+        tree("2 + 3 * 4"),
+        "\
+Add
+├─ 2
+└─ Multiply
+   ├─ 3
+   └─ 4
+"
+    );
+}
+
+#[test]
+fn continues_branches_of_earlier_siblings() {
+    assert_eq!(
+        tree("x = max(-(1 - y), 2.5)! ? pi : false"),
+        "\
+Assign x
+└─ Conditional
+   ├─ Factorial
+   │  └─ Call max
+   │     ├─ UnaryMinus
+   │     │  └─ Subtract
+   │     │     ├─ 1
+   │     │     └─ y
+   │     └─ 2.5
+   ├─ pi
+   └─ false
+"
+    );
+}
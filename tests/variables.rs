@@ -0,0 +1,170 @@
+mod common;
+
+use common::parse;
+use pest::Parser;
+use pest_calculator::{
+    parse_statement, CalcError, CalculatorParser, Environment, EvalError, EvalErrorKind, ExprKind,
+    Rule, Value,
+};
+
+fn execute(env: &mut Environment, input: &str) -> Result<Value, EvalError> {
+    let statement = parse(input);
+    env.eval(&statement)
+}
+
+#[test]
+fn parses_assignment() {
+    match parse("x = 1 + 2").kind {
+        ExprKind::Assign { name, value } => {
+            assert_eq!(name, "x");
+            assert_eq!(value.to_string(), "1 + 2");
+        }
+        other => panic!("expected an assignment, found {:?}", other),
+    }
+}
+
+#[test]
+fn assigned_variables_can_be_used_later() {
+    let mut env = Environment::new();
+    assert_eq!(execute(&mut env, "x = 5").unwrap(), Value::Int(5));
+    assert_eq!(execute(&mut env, "x * 2").unwrap(), Value::Int(10));
+    assert_eq!(execute(&mut env, "x = x + 1").unwrap(), Value::Int(6));
+    assert_eq!(env.get("x"), Some(Value::Int(6)));
+}
+
+#[test]
+fn chained_assignment_is_right_associative() {
+    let mut env = Environment::new();
+    assert_eq!(execute(&mut env, "a = b = 5").unwrap(), Value::Int(5));
+    assert_eq!(env.get("a"), Some(Value::Int(5)));
+    assert_eq!(env.get("b"), Some(Value::Int(5)));
+}
+
+#[test]
+fn assignment_inside_arithmetic() {
+    let mut env = Environment::new();
+    assert_eq!(execute(&mut env, "y = (x = 3) + 1").unwrap(), Value::Int(4));
+    assert_eq!(env.get("x"), Some(Value::Int(3)));
+    assert_eq!(env.get("y"), Some(Value::Int(4)));
+    assert_eq!(execute(&mut env, "2 * (x = x + 1)").unwrap(), Value::Int(8));
+    assert_eq!(env.get("x"), Some(Value::Int(4)));
+}
+
+#[test]
+fn assignment_has_the_lowest_precedence() {
+    let mut env = Environment::new();
+    assert_eq!(execute(&mut env, "x = 1 + 2 * 3").unwrap(), Value::Int(7));
+    assert_eq!(
+        execute(&mut env, "b = x > 5 && true").unwrap(),
+        Value::Bool(true)
+    );
+    assert_eq!(parse("a = 1 + (b = 2)").to_string(), "a = 1 + (b = 2)");
+    // The target of an assignment must be a plain variable.
+    assert!(CalculatorParser::parse(Rule::statement, "1 + x = 2").is_err());
+}
+
+#[test]
+fn undefined_variable_is_an_error() {
+    let mut env = Environment::new();
+    match execute(&mut env, "y + 1") {
+        Err(EvalError {
+            kind: EvalErrorKind::UndefinedVariable(name),
+            ..
+        }) => assert_eq!(name, "y"),
+        other => panic!("expected an undefined variable, found {:?}", other),
+    }
+}
+
+#[test]
+fn constants() {
+    let mut env = Environment::new();
+    assert_eq!(
+        execute(&mut env, "pi").unwrap(),
+        Value::Float(std::f64::consts::PI)
+    );
+    assert_eq!(
+        execute(&mut env, "pi * 2").unwrap(),
+        Value::Float(std::f64::consts::PI * 2.0)
+    );
+    assert_eq!(
+        execute(&mut env, "e").unwrap(),
+        Value::Float(std::f64::consts::E)
+    );
+}
+
+#[test]
+fn constants_cannot_be_assigned() {
+    let pairs = CalculatorParser::parse(Rule::statement, "pi = 3").unwrap();
+    match parse_statement(pairs) {
+        Err(CalcError::ReservedName(name)) => assert_eq!(name, "pi"),
+        other => panic!("expected a reserved name error, found {:?}", other),
+    }
+    // Names that merely start with a constant's name are ordinary variables.
+    let mut env = Environment::new();
+    assert_eq!(execute(&mut env, "pie = 3").unwrap(), Value::Int(3));
+}
+
+#[test]
+fn comparison_with_variable_is_not_an_assignment() {
+    let mut env = Environment::new();
+    execute(&mut env, "x = 1").unwrap();
+    assert_eq!(execute(&mut env, "x == 1").unwrap(), Value::Bool(true));
+    assert_eq!(env.get("x"), Some(Value::Int(1)));
+}
+
+#[test]
+fn ans_refers_to_the_previous_result_when_tracked() {
+    let mut env = Environment::new();
+    env.track_ans();
+    assert!(matches!(
+        execute(&mut env, "ans"),
+        Err(EvalError {
+            kind: EvalErrorKind::NoPreviousResult,
+            ..
+        })
+    ));
+    env.set_ans(Value::Int(5));
+    assert_eq!(execute(&mut env, "ans * 2").unwrap(), Value::Int(10));
+    execute(&mut env, "ans = 1").unwrap();
+    assert_eq!(execute(&mut env, "ans").unwrap(), Value::Int(1));
+}
+
+#[test]
+fn ans_is_an_ordinary_variable_by_default() {
+    let mut env = Environment::new();
+    env.set_ans(Value::Int(5));
+    assert!(matches!(
+        execute(&mut env, "ans"),
+        Err(EvalError {
+            kind: EvalErrorKind::UndefinedVariable(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn constant_names_ignore_case() {
+    let mut env = Environment::new();
+    assert_eq!(
+        execute(&mut env, "Pi").unwrap(),
+        Value::Float(std::f64::consts::PI)
+    );
+    assert_eq!(
+        execute(&mut env, "E").unwrap(),
+        Value::Float(std::f64::consts::E)
+    );
+    let pairs = CalculatorParser::parse(Rule::statement, "PI = 3").unwrap();
+    assert!(matches!(
+        parse_statement(pairs),
+        Err(CalcError::ReservedName(name)) if name == "PI"
+    ));
+}
+
+#[test]
+fn variable_names_are_case_sensitive() {
+    let mut env = Environment::new();
+    execute(&mut env, "x = 1").unwrap();
+    execute(&mut env, "X = 2").unwrap();
+    assert_eq!(execute(&mut env, "x").unwrap(), Value::Int(1));
+    assert_eq!(execute(&mut env, "X").unwrap(), Value::Int(2));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::parse;
+use pest_calculator::{walk, Expr, ExprVisitor, Op, Span};
+
+#[derive(Default)]
+struct CountBinOps(usize);
+
+impl<'a> ExprVisitor<'a> for CountBinOps {
+    fn visit_binop(&mut self, lhs: &'a Expr, _op: &'a Op, rhs: &'a Expr) {
+        self.0 += 1;
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+}
+
+fn count_binops(input: &str) -> usize {
+    let mut counter = CountBinOps::default();
+    counter.visit_expr(&parse(input));
+    counter.0
+}
+
+#[test]
+fn counts_binops() {
+    assert_eq!(count_binops("1"), 0);
+    assert_eq!(count_binops("1 + 2 * 3"), 2);
+    assert_eq!(
+        count_binops("max(1 - 2, -(3 / 4))! > 0 ? x = 1 + 1 : 2%"),
+        4
+    );
+}
+
+#[derive(Default)]
+struct Variables<'a>(Vec<&'a str>);
+
+impl<'a> ExprVisitor<'a> for Variables<'a> {
+    fn visit_variable(&mut self, name: &'a str) {
+        self.0.push(name);
+    }
+}
+
+#[test]
+fn visits_operands_in_source_order() {
+    let expr = parse("a + f(b, c) * (d ? h : g)");
+    let mut variables = Variables::default();
+    variables.visit_expr(&expr);
+    assert_eq!(variables.0, ["a", "b", "c", "d", "h", "g"]);
+}
+
+#[derive(Default)]
+struct Spans(Vec<Span>);
+
+impl<'a> ExprVisitor<'a> for Spans {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        self.0.push(expr.span);
+        walk(self, expr);
+    }
+}
+
+#[test]
+fn overriding_visit_expr_sees_every_node() {
+    let mut spans = Spans::default();
+    spans.visit_expr(&parse("1 + -2"));
+    assert_eq!(
+        spans.0,
+        [
+            Span::new(0, 6),
+            Span::new(0, 1),
+            Span::new(4, 6),
+            Span::new(5, 6)
+        ]
+    );
+}
+
+#[test]
+fn constant_expressions() {
+    assert!(parse("1 + 2 * -3").is_constant());
+    assert!(parse("2 * pi > e ? 1% : 3!").is_constant());
+    assert!(parse("1 + 2").variables().is_empty());
+}
+
+#[test]
+fn expressions_with_variables() {
+    let expr = parse("x * x + y");
+    assert!(!expr.is_constant());
+    assert_eq!(expr.variables(), ["x", "y"].map(String::from).into());
+    assert!(!parse("abs(1)").is_constant());
+    assert!(!parse("x = 1").is_constant());
+}
+
+#[test]
+fn variables_in_deeply_nested_expressions() {
+    let expr = parse("-(a + -(2 * (b - max(c, -(-(d ^ 2)))))) ? (z = q) : !w");
+    assert!(!expr.is_constant());
+    assert_eq!(
+        expr.variables(),
+        ["a", "b", "c", "d", "q", "w"].map(String::from).into()
+    );
+}